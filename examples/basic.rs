@@ -1,11 +1,10 @@
 use stagehand_sdk::{
-    ActResponseEvent, AgentConfig, AgentExecuteOptions, Env, ExecuteResponseEvent,
-    ExtractResponseEvent, Model, ModelConfiguration, ObserveResponseEvent, Stagehand,
-    TransportChoice, V3Options,
+    ActOptions, ActResponseEvent, AgentConfig, AgentExecuteOptions, Env, ExecuteResponseEvent,
+    ExtractOptions, ExtractResponseEvent, Model, ModelConfiguration, ObserveOptions,
+    ObserveResponseEvent, ObservedAction, Stagehand, TransportChoice, V3Options,
 };
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Comment {
@@ -47,18 +46,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut act_stream = stagehand
         .act(
             "Navigate to https://news.ycombinator.com",
-            None,
-            HashMap::new(),
-            Some(60_000),
-            None,
+            ActOptions::new().timeout(60_000),
         )
         .await?;
 
     while let Some(res) = act_stream.next().await {
-        if let Ok(response) = res {
-            if let Some(ActResponseEvent::Success(success)) = response.event {
-                println!("   Navigation success: {}\n", success);
-            }
+        if let Ok(response) = res
+            && let Some(ActResponseEvent::Success(success)) = response.event
+        {
+            println!("   Navigation success: {}\n", success);
         }
     }
 
@@ -66,41 +62,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("4. Finding link to view comments for the top post...");
     let mut observe_stream = stagehand
         .observe(
-            Some("Find the link to view comments for the top post".to_string()),
-            None,
-            Some(60_000),
-            None,
-            None,
+            ObserveOptions::new()
+                .instruction("Find the link to view comments for the top post")
+                .timeout(60_000),
         )
         .await?;
 
     let mut elements_json = String::new();
     while let Some(res) = observe_stream.next().await {
-        if let Ok(response) = res {
-            if let Some(ObserveResponseEvent::ElementsJson(json)) = response.event {
-                elements_json = json;
-                println!("   Found elements!\n");
-            }
+        if let Ok(response) = res
+            && let Some(ObserveResponseEvent::ElementsJson(json)) = response.event
+        {
+            elements_json = json;
+            println!("   Found elements!\n");
         }
     }
 
-    // 5. Act on the first action from observe results
+    // 5. Act on the first action from observe results (no second LLM call)
     println!("5. Clicking on the comments link...");
+    let actions: Vec<ObservedAction> = serde_json::from_str(&elements_json)?;
+    let action = actions
+        .into_iter()
+        .next()
+        .ok_or("observe returned no actions")?;
+    println!(
+        "   Acting on: {} ({})\n",
+        action.description, action.selector
+    );
     let mut act_stream = stagehand
-        .act(
-            "Click on the comments link for the top post",
-            None,
-            HashMap::new(),
-            Some(60_000),
-            None,
-        )
+        .act_with_action(action, ActOptions::new().timeout(60_000))
         .await?;
 
     while let Some(res) = act_stream.next().await {
-        if let Ok(response) = res {
-            if let Some(ActResponseEvent::Success(success)) = response.event {
-                println!("   Click success: {}\n", success);
-            }
+        if let Ok(response) = res
+            && let Some(ActResponseEvent::Success(success)) = response.event
+        {
+            println!("   Click success: {}\n", success);
         }
     }
 
@@ -119,21 +116,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .extract(
             "Extract the text and author of the top comment on the page",
             schema,
-            None,
-            Some(60_000),
-            None,
-            None,
+            ExtractOptions::new().timeout(60_000),
         )
         .await?;
 
     let mut comment_data = String::new();
     while let Some(res) = extract_stream.next().await {
-        if let Ok(response) = res {
-            if let Some(ExtractResponseEvent::DataJson(json)) = response.event {
-                comment_data = json.clone();
-                if let Ok(comment) = serde_json::from_str::<Comment>(&json) {
-                    println!("   Top comment by {}: {}\n", comment.author, comment.text);
-                }
+        if let Ok(response) = res
+            && let Some(ExtractResponseEvent::DataJson(json)) = response.event
+        {
+            comment_data = json.clone();
+            if let Ok(comment) = serde_json::from_str::<Comment>(&json) {
+                println!("   Top comment by {}: {}\n", comment.author, comment.text);
             }
         }
     }
@@ -2,10 +2,12 @@
 //!
 //! What this demonstrates:
 //! - Start a Stagehand session (remote Stagehand API / Browserbase browser)
-//! - Attach chromiumoxide to the same browser via CDP (`browserbase_cdp_url`)
-//! - Use a helper to convert a chromiumoxide `Page` into the Stagehand `frame_id`
+//! - Attach chromiumoxide to the same browser via CDP (`active_page`)
+//! - Use the `Page` handle's `frame_id` so Stagehand targets the same page
 //!   so Stagehand uses the correct page in `observe/act/extract`.
 //!
+//! Run with `cargo run --example chromiumoxide_page_example --features chromiumoxide`.
+//!
 //! Environment variables required:
 //! - MODEL_API_KEY (or another supported model provider API key)
 //! - BROWSERBASE_API_KEY
@@ -14,14 +16,12 @@
 //! Optional:
 //! - STAGEHAND_BASE_URL (defaults to https://api.stagehand.browserbase.com/v1)
 
-use chromiumoxide::browser::Browser;
-use chromiumoxide::cdp::browser_protocol::page::{GetFrameTreeParams, NavigateParams};
+use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
 use futures::StreamExt;
 use stagehand_sdk::{
-    ActResponseEvent, Env, ExtractResponseEvent, Model, ObserveResponseEvent, Stagehand,
-    TransportChoice, V3Options,
+    ActOptions, ActResponseEvent, Env, ExtractOptions, ExtractResponseEvent, Model,
+    ObserveOptions, ObserveResponseEvent, Stagehand, TransportChoice, V3Options,
 };
-use std::collections::HashMap;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -43,55 +43,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     stagehand.start(opts).await?;
     println!("   Session ID: {:?}\n", stagehand.session_id());
 
-    println!("3. Fetching Browserbase CDP URL...");
-    let cdp_url = stagehand.browserbase_cdp_url().await?;
-    println!("   CDP URL: {cdp_url}\n");
-
-    println!("4. Connecting chromiumoxide over CDP...");
-    let (browser, mut handler) = Browser::connect(&cdp_url).await?;
-    let handler_task = tokio::spawn(async move {
-        while let Some(event) = handler.next().await {
-            if event.is_err() {
-                break;
-            }
-        }
-    });
-    println!("   Connected!\n");
-
-    println!("5. Getting a page and navigating with chromiumoxide...");
-    let pages = browser.pages().await?;
-    let page = if pages.is_empty() {
-        browser.new_page("about:blank").await?
-    } else {
-        pages.into_iter().next().unwrap()
-    };
-
-    page.execute(NavigateParams::builder().url("https://example.com").build()?)
+    println!("3-5. Connecting chromiumoxide over CDP and navigating the active page...");
+    let page = stagehand.active_page().await?;
+    page.cdp().execute(NavigateParams::builder().url("https://example.com").build()?)
         .await?;
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     println!("   Chromiumoxide navigation complete.\n");
 
-    println!("6. Resolving Stagehand frame_id from chromiumoxide page via Page.getFrameTree...");
-      let frame_tree = page.execute(GetFrameTreeParams::default()).await?.result.frame_tree;
-      let frame_id = frame_tree.frame.id.inner().clone();
+    println!("6. Stagehand frame_id of the page...");
+    let frame_id = page.frame_id().to_string();
     println!("   frame_id: {frame_id}\n");
 
     println!("7. Stagehand.observe(frame_id=...) ...");
     let mut observe_stream = stagehand
         .observe(
-            Some("Find the most relevant click target on this page".to_string()),
-            None,
-            Some(30_000),
-            None,
-            Some(frame_id.clone()),
+            ObserveOptions::new()
+                .instruction("Find the most relevant click target on this page")
+                .timeout(30_000)
+                .frame_id(frame_id.clone()),
         )
         .await?;
 
     while let Some(msg) = observe_stream.next().await {
-        if let Ok(event) = msg {
-            if let Some(ObserveResponseEvent::ElementsJson(json)) = event.event {
-                println!("   Observed elements JSON: {json}");
-            }
+        if let Ok(event) = msg
+            && let Some(ObserveResponseEvent::ElementsJson(json)) = event.event
+        {
+            println!("   Observed elements JSON: {json}");
         }
     }
 
@@ -108,18 +85,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .extract(
             "Extract the page title and current URL",
             schema,
-            None,
-            Some(30_000),
-            None,
-            Some(frame_id.clone()),
+            ExtractOptions::new().timeout(30_000).frame_id(frame_id.clone()),
         )
         .await?;
 
     while let Some(msg) = extract_stream.next().await {
-        if let Ok(event) = msg {
-            if let Some(ExtractResponseEvent::DataJson(json)) = event.event {
-                println!("   Extracted: {json}");
-            }
+        if let Ok(event) = msg
+            && let Some(ExtractResponseEvent::DataJson(json)) = event.event
+        {
+            println!("   Extracted: {json}");
         }
     }
 
@@ -127,23 +101,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut act_stream = stagehand
         .act(
             "Click on the 'More information...' link",
-            None,
-            HashMap::new(),
-            Some(30_000),
-            Some(frame_id.clone()),
+            ActOptions::new().timeout(30_000).frame_id(frame_id.clone()),
         )
         .await?;
 
     while let Some(msg) = act_stream.next().await {
-        if let Ok(event) = msg {
-            if let Some(ActResponseEvent::Success(success)) = event.event {
-                println!("   Act success: {success}");
-            }
+        if let Ok(event) = msg
+            && let Some(ActResponseEvent::Success(success)) = event.event
+        {
+            println!("   Act success: {success}");
         }
     }
 
     println!("\n10. Cleaning up...");
-    handler_task.abort();
     stagehand.end().await?;
     println!("   Done.");
 
@@ -0,0 +1,94 @@
+//! Accessibility trees, for cheap deterministic reads of what a page shows.
+
+use serde::{Deserialize, Serialize};
+
+/// A node of a page's accessibility tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityNode {
+    pub role: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// DOM node id, usable with CDP DOM methods
+    #[serde(default)]
+    pub backend_node_id: Option<i64>,
+    #[serde(default)]
+    pub children: Vec<AccessibilityNode>,
+}
+
+impl AccessibilityNode {
+    /// This node and all its descendants, depth first
+    pub fn iter(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Indented `role "name"` outline, one node per line, for logs and snapshots
+    pub fn to_outline(&self) -> String {
+        let mut outline = String::new();
+        self.write_outline(0, &mut outline);
+        outline
+    }
+
+    fn write_outline(&self, depth: usize, out: &mut String) {
+        out.push_str(&"  ".repeat(depth));
+        out.push_str(&self.role);
+        if !self.name.is_empty() {
+            out.push_str(&format!(" {:?}", self.name));
+        }
+        if let Some(value) = &self.value {
+            out.push_str(&format!(" = {:?}", value));
+        }
+        out.push('\n');
+        for child in &self.children {
+            child.write_outline(depth + 1, out);
+        }
+    }
+}
+
+/// Build the tree from the flat node list returned by CDP `Accessibility.getFullAXTree`.
+///
+/// Ignored nodes are left out and their children attached to the nearest kept ancestor.
+#[cfg(feature = "chromiumoxide")]
+pub fn from_cdp_nodes(nodes: &[chromiumoxide::cdp::browser_protocol::accessibility::AxNode]) -> Option<AccessibilityNode> {
+    use chromiumoxide::cdp::browser_protocol::accessibility::{AxNode, AxValue};
+    use std::collections::HashMap;
+
+    fn text(value: &Option<AxValue>) -> Option<String> {
+        match value.as_ref()?.value.as_ref()? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Null => None,
+            other => Some(other.to_string()),
+        }
+    }
+
+    fn build(node: &AxNode, by_id: &HashMap<&str, &AxNode>) -> Vec<AccessibilityNode> {
+        let children: Vec<_> = node.child_ids.iter().flatten()
+            .filter_map(|id| by_id.get(id.inner().as_str()))
+            .flat_map(|child| build(child, by_id))
+            .collect();
+        if node.ignored {
+            return children;
+        }
+        vec![AccessibilityNode {
+            role: text(&node.role).unwrap_or_default(),
+            name: text(&node.name).unwrap_or_default(),
+            value: text(&node.value).filter(|v| !v.is_empty()),
+            description: text(&node.description).filter(|d| !d.is_empty()),
+            backend_node_id: node.backend_dom_node_id.as_ref().map(|id| *id.inner()),
+            children,
+        }]
+    }
+
+    let by_id: HashMap<&str, &AxNode> = nodes.iter().map(|n| (n.node_id.inner().as_str(), n)).collect();
+    let root = nodes.iter().find(|n| n.parent_id.is_none())?;
+    build(root, &by_id).into_iter().next()
+}
@@ -0,0 +1,634 @@
+//! Client for the Browserbase platform API.
+//!
+//! Stagehand sessions run on Browserbase browsers. This module manages those browser sessions
+//! directly (create, inspect, list, release, live view URLs and logs) with the same
+//! `BROWSERBASE_API_KEY` / `BROWSERBASE_PROJECT_ID` credentials the Stagehand transport uses.
+
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+
+use crate::recording::RecordingEvent;
+use crate::{DEFAULT_BROWSERBASE_API_URL, StagehandError};
+
+/// Directory on the session's machine where uploaded files are stored
+pub const SESSION_UPLOADS_DIR: &str = "/tmp/.uploads";
+
+// =============================================================================
+// Session Types
+// =============================================================================
+
+/// Status of a Browserbase session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum SessionStatus {
+    Running,
+    Error,
+    TimedOut,
+    Completed,
+    /// A status added to the API after this SDK version
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionStatus::Running => write!(f, "RUNNING"),
+            SessionStatus::Error => write!(f, "ERROR"),
+            SessionStatus::TimedOut => write!(f, "TIMED_OUT"),
+            SessionStatus::Completed => write!(f, "COMPLETED"),
+            SessionStatus::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// Status that can be requested through [`BrowserbaseClient::update_session`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum SessionUpdateStatus {
+    /// Ask Browserbase to end the session and release the browser
+    RequestRelease,
+}
+
+/// A Browserbase browser session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    #[serde(default)]
+    pub project_id: String,
+    pub status: SessionStatus,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub keep_alive: bool,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub context_id: Option<String>,
+    #[serde(default)]
+    pub proxy_bytes: Option<u64>,
+    #[serde(default)]
+    pub avg_cpu_usage: Option<f64>,
+    #[serde(default)]
+    pub memory_usage: Option<u64>,
+    /// CDP WebSocket URL, only returned when the session is created or fetched individually
+    #[serde(default)]
+    pub connect_url: Option<String>,
+    #[serde(default)]
+    pub selenium_remote_url: Option<String>,
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    #[serde(default)]
+    pub user_metadata: Option<serde_json::Value>,
+}
+
+/// Live view URLs for a running session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLiveUrls {
+    pub debugger_fullscreen_url: String,
+    pub debugger_url: String,
+    pub ws_url: String,
+    #[serde(default)]
+    pub pages: Vec<LivePage>,
+}
+
+/// A page (tab) in a running session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivePage {
+    pub id: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub favicon_url: Option<String>,
+    pub debugger_url: String,
+    pub debugger_fullscreen_url: String,
+}
+
+/// A CDP log entry recorded for a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLog {
+    pub method: String,
+    #[serde(default)]
+    pub page_id: i64,
+    #[serde(default)]
+    pub session_id: String,
+    #[serde(default)]
+    pub frame_id: Option<String>,
+    #[serde(default)]
+    pub loader_id: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub request: Option<serde_json::Value>,
+    #[serde(default)]
+    pub response: Option<serde_json::Value>,
+}
+
+/// A Browserbase context: browser state (cookies, local storage, ...) that can be loaded into
+/// sessions and optionally persisted back when they end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserbaseContext {
+    pub id: String,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Upload URL and encryption details, only returned when the context is created
+    #[serde(default)]
+    pub upload_url: Option<String>,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub cipher_algorithm: Option<String>,
+    #[serde(default)]
+    pub initialization_vector_size: Option<u32>,
+}
+
+/// A file downloaded by the session's browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadedFile {
+    /// Path relative to the archive root with `/` separators, e.g. `exports/report.csv`. Never
+    /// absolute and never contains `..`.
+    pub name: String,
+    pub bytes: Vec<u8>,
+    /// MIME type guessed from the file extension
+    pub mime: String,
+}
+
+// =============================================================================
+// Session Create Parameters
+// =============================================================================
+
+/// Body for creating a Browserbase session.
+///
+/// Fields the SDK does not model yet can be passed through `extra`, which is flattened into the
+/// request body.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserbaseSessionCreateParams {
+    /// Defaults to the client's project when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser_settings: Option<BrowserSettings>,
+    /// Session timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxies: Option<Proxies>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl BrowserbaseSessionCreateParams {
+    /// Load the context `context_id` into the session, saving changes back to it when the session
+    /// ends if `persist` is true
+    pub fn with_context(mut self, context_id: impl Into<String>, persist: bool) -> Self {
+        self.browser_settings.get_or_insert_with(Default::default).context = Some(BrowserContextSettings {
+            id: context_id.into(),
+            persist: Some(persist),
+        });
+        self
+    }
+}
+
+/// Browser configuration for a new session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<BrowserContextSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<Viewport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_ads: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solve_captchas: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_session: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_session: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advanced_stealth: Option<bool>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A persistent browser context to load into the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserContextSettings {
+    pub id: String,
+    /// Save changes made during the session back to the context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persist: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Constraints for the generated browser fingerprint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub browsers: Vec<FingerprintBrowser>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub devices: Vec<FingerprintDevice>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locales: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operating_systems: Vec<FingerprintOperatingSystem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screen: Option<FingerprintScreen>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintBrowser {
+    Chrome,
+    Edge,
+    Firefox,
+    Safari,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintDevice {
+    Desktop,
+    Mobile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintOperatingSystem {
+    Android,
+    Ios,
+    Linux,
+    Macos,
+    Windows,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintScreen {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<u32>,
+}
+
+/// Proxy configuration: `true` for Browserbase's default proxies, or a list of routing rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Proxies {
+    Enabled(bool),
+    Custom(Vec<ProxyConfig>),
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ProxyConfig {
+    /// Browserbase-managed proxy, optionally pinned to a location
+    #[serde(rename_all = "camelCase")]
+    Browserbase {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        geolocation: Option<ProxyGeolocation>,
+        /// Only route hostnames matching this pattern through the proxy
+        #[serde(skip_serializing_if = "Option::is_none")]
+        domain_pattern: Option<String>,
+    },
+    /// Your own proxy server
+    #[serde(rename_all = "camelCase")]
+    External {
+        server: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        username: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        domain_pattern: Option<String>,
+    },
+}
+
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyConfig::Browserbase { geolocation, domain_pattern } => f
+                .debug_struct("Browserbase")
+                .field("geolocation", geolocation)
+                .field("domain_pattern", domain_pattern)
+                .finish(),
+            ProxyConfig::External { server, username, password, domain_pattern } => f
+                .debug_struct("External")
+                .field("server", server)
+                .field("username", username)
+                .field("password", &password.as_ref().map(|_| "********"))
+                .field("domain_pattern", domain_pattern)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyGeolocation {
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+}
+
+/// Region the session's browser runs in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Region {
+    #[serde(rename = "us-west-2")]
+    UsWest2,
+    #[serde(rename = "us-east-1")]
+    UsEast1,
+    #[serde(rename = "eu-central-1")]
+    EuCentral1,
+    #[serde(rename = "ap-southeast-1")]
+    ApSoutheast1,
+    /// A region not listed above
+    #[serde(untagged)]
+    Other(String),
+}
+
+// =============================================================================
+// Client
+// =============================================================================
+
+/// Typed client for the Browserbase REST API.
+///
+/// Cheap to clone; clones share the underlying HTTP connection pool.
+#[derive(Clone)]
+pub struct BrowserbaseClient {
+    base_url: String,
+    api_key: String,
+    project_id: String,
+    client: Arc<Client>,
+}
+
+impl fmt::Debug for BrowserbaseClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrowserbaseClient")
+            .field("base_url", &self.base_url)
+            .field("api_key", &"********")
+            .field("project_id", &self.project_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BrowserbaseClient {
+    pub fn new(api_key: impl Into<String>, project_id: impl Into<String>) -> Self {
+        Self {
+            base_url: DEFAULT_BROWSERBASE_API_URL.to_string(),
+            api_key: api_key.into(),
+            project_id: project_id.into(),
+            client: Arc::new(Client::new()),
+        }
+    }
+
+    /// Create a client from `BROWSERBASE_API_KEY` and `BROWSERBASE_PROJECT_ID`, using
+    /// `BROWSERBASE_API_URL` as the base URL if set.
+    pub fn from_env() -> Result<Self, StagehandError> {
+        let api_key = std::env::var("BROWSERBASE_API_KEY")
+            .map_err(|_| StagehandError::MissingApiKey("BROWSERBASE_API_KEY".to_string()))?;
+        let project_id = std::env::var("BROWSERBASE_PROJECT_ID")
+            .map_err(|_| StagehandError::MissingApiKey("BROWSERBASE_PROJECT_ID".to_string()))?;
+        let base_url = std::env::var("BROWSERBASE_API_URL")
+            .unwrap_or_else(|_| DEFAULT_BROWSERBASE_API_URL.to_string());
+        Ok(Self::new(api_key, project_id).with_base_url(base_url))
+    }
+
+    /// Override the API base URL (defaults to [`DEFAULT_BROWSERBASE_API_URL`])
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn project_id(&self) -> &str {
+        &self.project_id
+    }
+
+    /// Create a new browser session.
+    ///
+    /// `params.project_id` defaults to this client's project.
+    pub async fn create_session(&self, params: &BrowserbaseSessionCreateParams) -> Result<Session, StagehandError> {
+        let mut body = params.clone();
+        body.project_id.get_or_insert_with(|| self.project_id.clone());
+        self.send(self.client.post(self.url("/sessions")).json(&body), "create session").await
+    }
+
+    pub async fn get_session(&self, session_id: &str) -> Result<Session, StagehandError> {
+        self.send(self.client.get(self.url(&format!("/sessions/{}", session_id))), "fetch session").await
+    }
+
+    /// List sessions in the project, optionally filtered by status
+    pub async fn list_sessions(&self, status: Option<SessionStatus>) -> Result<Vec<Session>, StagehandError> {
+        let mut request = self.client.get(self.url("/sessions"));
+        if let Some(status) = status {
+            request = request.query(&[("status", status.to_string())]);
+        }
+        self.send(request, "list sessions").await
+    }
+
+    pub async fn update_session(&self, session_id: &str, status: SessionUpdateStatus) -> Result<Session, StagehandError> {
+        let body = serde_json::json!({ "projectId": self.project_id, "status": status });
+        self.send(self.client.post(self.url(&format!("/sessions/{}", session_id))).json(&body), "update session").await
+    }
+
+    /// Shorthand for [`update_session`](Self::update_session) with `REQUEST_RELEASE`
+    pub async fn release_session(&self, session_id: &str) -> Result<Session, StagehandError> {
+        self.update_session(session_id, SessionUpdateStatus::RequestRelease).await
+    }
+
+    /// Live view (debugger) URLs for a running session
+    pub async fn session_live_urls(&self, session_id: &str) -> Result<SessionLiveUrls, StagehandError> {
+        self.send(self.client.get(self.url(&format!("/sessions/{}/debug", session_id))), "fetch session debug URLs").await
+    }
+
+    pub async fn session_logs(&self, session_id: &str) -> Result<Vec<SessionLog>, StagehandError> {
+        self.send(self.client.get(self.url(&format!("/sessions/{}/logs", session_id))), "fetch session logs").await
+    }
+
+    /// rrweb events recorded for the session
+    pub async fn session_recording(&self, session_id: &str) -> Result<Vec<RecordingEvent>, StagehandError> {
+        self.send(self.client.get(self.url(&format!("/sessions/{}/recording", session_id))), "fetch session recording").await
+    }
+
+    /// Files downloaded during the session, unpacked from the archive Browserbase serves.
+    ///
+    /// Browserbase syncs downloads shortly after they finish, so a file from a click that just
+    /// happened may not be listed yet.
+    pub async fn session_downloads(&self, session_id: &str) -> Result<Vec<DownloadedFile>, StagehandError> {
+        let archive = self.execute(self.client.get(self.url(&format!("/sessions/{}/downloads", session_id))), "fetch session downloads").await?
+            .bytes().await
+            .map_err(|e| StagehandError::Api(format!("Failed to fetch session downloads: {}", e)))?;
+        crate::spawn_blocking(move || unpack_downloads(&archive)).await
+    }
+
+    /// Upload a local file to the session's machine and return its path there, which can be set
+    /// on a file input
+    pub async fn upload_file(&self, session_id: &str, path: impl AsRef<std::path::Path>) -> Result<String, StagehandError> {
+        let path = path.as_ref();
+        let name = path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| StagehandError::Io(format!("Not a file path: {}", path.display())))?;
+        let file = path.to_path_buf();
+        let bytes = crate::spawn_blocking(move || std::fs::read(file)).await?;
+
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(name.clone())
+            .mime_str(mime_guess::from_path(&name).first_or_octet_stream().as_ref())?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+        self.execute(self.client.post(self.url(&format!("/sessions/{}/uploads", session_id))).multipart(form), "upload file").await?;
+        Ok(format!("{}/{}", SESSION_UPLOADS_DIR, name))
+    }
+
+    /// Create an empty context in this client's project
+    pub async fn create_context(&self) -> Result<BrowserbaseContext, StagehandError> {
+        let body = serde_json::json!({ "projectId": self.project_id });
+        self.send(self.client.post(self.url("/contexts")).json(&body), "create context").await
+    }
+
+    pub async fn get_context(&self, context_id: &str) -> Result<BrowserbaseContext, StagehandError> {
+        self.send(self.client.get(self.url(&format!("/contexts/{}", context_id))), "fetch context").await
+    }
+
+    pub async fn delete_context(&self, context_id: &str) -> Result<(), StagehandError> {
+        self.execute(self.client.delete(self.url(&format!("/contexts/{}", context_id))), "delete context").await?;
+        Ok(())
+    }
+
+    /// CDP WebSocket URL for a session, normalized so it can be passed to CDP clients directly
+    pub async fn session_connect_url(&self, session_id: &str) -> Result<String, StagehandError> {
+        let session = self.get_session(session_id).await?;
+        let connect_url = session.connect_url
+            .ok_or_else(|| StagehandError::Api("Session response missing connectUrl".to_string()))?;
+        Ok(normalize_connect_url(&connect_url))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder, action: &str) -> Result<T, StagehandError> {
+        self.execute(request, action).await?
+            .json().await
+            .map_err(|e| StagehandError::Api(format!("Failed to parse {} response: {}", action, e)))
+    }
+
+    async fn execute(&self, request: reqwest::RequestBuilder, action: &str) -> Result<reqwest::Response, StagehandError> {
+        let response = request
+            .header("x-bb-api-key", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| StagehandError::Api(format!("Failed to {}: {}", action, e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StagehandError::Api(format!("Failed to {}: HTTP {} {}", action, status, body).trim_end().to_string()));
+        }
+        Ok(response)
+    }
+}
+
+/// Cap on the total bytes unpacked from one downloads archive, so a small archive that inflates
+/// to gigabytes fails instead of exhausting memory
+const MAX_UNPACKED_DOWNLOADS: u64 = 128 * 1024 * 1024;
+
+fn unpack_downloads(archive: &[u8]) -> Result<Vec<DownloadedFile>, StagehandError> {
+    // A session without downloads may return an empty body instead of an empty archive
+    if archive.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let unpack_error = |e: &dyn fmt::Display| StagehandError::Api(format!("Failed to unpack session downloads: {}", e));
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive)).map_err(|e| unpack_error(&e))?;
+    let mut files = Vec::with_capacity(zip.len());
+    let mut remaining = MAX_UNPACKED_DOWNLOADS;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| unpack_error(&e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Drop entries whose path would escape a target directory
+        let Some(path) = entry.enclosed_name() else {
+            continue;
+        };
+        let name = path.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        // The declared size comes from the archive, so it is neither trusted for allocation nor
+        // for the limit; reading one byte past what is left detects an oversized entry
+        let mut bytes = Vec::new();
+        (&mut entry).take(remaining + 1).read_to_end(&mut bytes).map_err(|e| unpack_error(&e))?;
+        remaining = remaining.checked_sub(bytes.len() as u64).ok_or_else(|| {
+            unpack_error(&format_args!("files exceed {} MiB in total", MAX_UNPACKED_DOWNLOADS / (1024 * 1024)))
+        })?;
+        let mime = mime_guess::from_path(&name).first_or_octet_stream().to_string();
+        files.push(DownloadedFile { name, bytes, mime });
+    }
+    Ok(files)
+}
+
+/// Browserbase returns URLs like "wss://host?query" but AWS ELB requires a path before the
+/// query string. We normalize to "wss://host/?query".
+fn normalize_connect_url(connect_url: &str) -> String {
+    if let Some((before_query, query)) = connect_url.split_once('?')
+        // Check if there's no path after the host (no '/' after "://")
+        && let Some((_, after_scheme)) = before_query.split_once("://")
+        && !after_scheme.contains('/')
+    {
+        return format!("{}/?{}", before_query, query);
+    }
+    connect_url.to_string()
+}
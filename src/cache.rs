@@ -0,0 +1,413 @@
+//! Client-side caching of observed actions for `act` and results for `extract`.
+//!
+//! When an [`ActionCache`] is set on [`Stagehand`](crate::Stagehand), `act` first replays the
+//! cached [`ObservedAction`]s for the page + frame URL + instruction + variables deterministically, and
+//! only falls back to LLM inference (updating the cache) when there is no entry or the replay fails.
+//!
+//! [`ExtractCache`] does the same for `extract` results, keyed on the page content.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{ObservedAction, StagehandError, VariableValue};
+
+// =============================================================================
+// Cache Key
+// =============================================================================
+
+/// Key identifying a cached action
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionCacheKey {
+    /// Page URL without query string or fragment
+    pub url_pattern: String,
+    pub instruction: String,
+    /// Stable hash of the variable names and values (values are never stored)
+    pub variables_hash: String,
+    /// URL without query string or fragment of the iframe the instruction targets, `None` for
+    /// the page's main frame. CDP frame ids differ on every run, so frames are keyed by URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+}
+
+impl ActionCacheKey {
+    /// Key for `instruction` on the page at `url`, in the iframe at `frame_url` if any
+    pub fn new(url: &str, instruction: &str, variables: &HashMap<String, VariableValue>, frame_url: Option<&str>) -> Self {
+        let mut pairs: Vec<_> = variables.iter().collect();
+        pairs.sort_by_key(|(name, _)| *name);
+        let mut buf = Vec::new();
+        for (name, value) in pairs {
+            buf.extend_from_slice(name.as_bytes());
+            buf.push(0);
+            // An unsalted hash of a secret is guessable offline, so only its name is part of the key
+            if !value.is_secret() {
+                buf.extend_from_slice(value.expose().as_bytes());
+            }
+            buf.push(0);
+        }
+
+        Self {
+            url_pattern: url_pattern(url),
+            instruction: instruction.to_string(),
+            variables_hash: stable_hash(&buf),
+            frame: frame_url.map(url_pattern),
+        }
+    }
+}
+
+/// Normalize a URL into the pattern used for cache keys by dropping the query and fragment
+fn url_pattern(url: &str) -> String {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    url[..end].trim_end_matches('/').to_string()
+}
+
+/// 64-bit FNV-1a hash, hex encoded. Stable across processes and Rust versions,
+/// so it is safe to persist.
+pub(crate) fn stable_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+// =============================================================================
+// Cache Trait
+// =============================================================================
+
+/// Storage backend for cached actions. An entry holds every action one `act` performed, in order.
+///
+/// `put` and `remove` are called off the async executor, so they may block on IO. A failed `put`
+/// doesn't fail the `act` it belongs to; the error is reported as a log event of that act.
+pub trait ActionCache: Send + Sync {
+    fn get(&self, key: &ActionCacheKey) -> Result<Option<Vec<ObservedAction>>, StagehandError>;
+    fn put(&self, key: &ActionCacheKey, actions: Vec<ObservedAction>) -> Result<(), StagehandError>;
+    fn remove(&self, key: &ActionCacheKey) -> Result<(), StagehandError>;
+}
+
+// =============================================================================
+// In-Memory Backend
+// =============================================================================
+
+/// Action cache that lives for the duration of the process
+#[derive(Debug, Default)]
+pub struct InMemoryActionCache {
+    entries: Mutex<HashMap<ActionCacheKey, Vec<ObservedAction>>>,
+}
+
+impl InMemoryActionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ActionCache for InMemoryActionCache {
+    fn get(&self, key: &ActionCacheKey) -> Result<Option<Vec<ObservedAction>>, StagehandError> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    fn put(&self, key: &ActionCacheKey, actions: Vec<ObservedAction>) -> Result<(), StagehandError> {
+        self.entries.lock().unwrap().insert(key.clone(), actions);
+        Ok(())
+    }
+
+    fn remove(&self, key: &ActionCacheKey) -> Result<(), StagehandError> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+// =============================================================================
+// JSON File Backend
+// =============================================================================
+
+#[derive(Serialize, Deserialize)]
+struct JsonCacheEntry {
+    key: ActionCacheKey,
+    actions: Vec<ObservedAction>,
+}
+
+/// Action cache persisted to a JSON file, which can be checked into a repository.
+///
+/// The file is read once when opened and rewritten on every change.
+#[derive(Debug)]
+pub struct JsonFileActionCache {
+    path: PathBuf,
+    entries: Mutex<HashMap<ActionCacheKey, Vec<ObservedAction>>>,
+}
+
+impl JsonFileActionCache {
+    /// Open the cache at `path`, starting empty if the file does not exist yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StagehandError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<JsonCacheEntry>>(&bytes)
+                .map_err(|e| StagehandError::Cache(format!("Failed to parse {}: {}", path.display(), e)))?
+                .into_iter()
+                .map(|entry| (entry.key, entry.actions))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(StagehandError::Cache(format!("Failed to read {}: {}", path.display(), e))),
+        };
+        Ok(Self { path, entries: Mutex::new(entries) })
+    }
+
+    fn save(&self, entries: &HashMap<ActionCacheKey, Vec<ObservedAction>>) -> Result<(), StagehandError> {
+        let mut list: Vec<_> = entries
+            .iter()
+            .map(|(key, actions)| JsonCacheEntry { key: key.clone(), actions: actions.clone() })
+            .collect();
+        // Sort so the file diffs cleanly between runs
+        list.sort_by(|a, b| {
+            (&a.key.url_pattern, &a.key.instruction, &a.key.variables_hash, &a.key.frame)
+                .cmp(&(&b.key.url_pattern, &b.key.instruction, &b.key.variables_hash, &b.key.frame))
+        });
+        let json = serde_json::to_vec_pretty(&list).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        std::fs::write(&self.path, json)
+            .map_err(|e| StagehandError::Cache(format!("Failed to write {}: {}", self.path.display(), e)))
+    }
+}
+
+impl ActionCache for JsonFileActionCache {
+    fn get(&self, key: &ActionCacheKey) -> Result<Option<Vec<ObservedAction>>, StagehandError> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    fn put(&self, key: &ActionCacheKey, actions: Vec<ObservedAction>) -> Result<(), StagehandError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.clone(), actions);
+        self.save(&entries)
+    }
+
+    fn remove(&self, key: &ActionCacheKey) -> Result<(), StagehandError> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(key).is_some() {
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Sled Backend
+// =============================================================================
+
+/// Action cache backed by an embedded sled database, for large or shared caches
+#[cfg(feature = "sled-cache")]
+pub struct SledActionCache {
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled-cache")]
+impl SledActionCache {
+    /// Open (or create) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StagehandError> {
+        let db = sled::open(path).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        Self::from_db(&db)
+    }
+
+    /// Use the `stagehand_actions` tree of an existing database
+    pub fn from_db(db: &sled::Db) -> Result<Self, StagehandError> {
+        let tree = db.open_tree("stagehand_actions").map_err(|e| StagehandError::Cache(e.to_string()))?;
+        Ok(Self { tree })
+    }
+
+    fn encode_key(key: &ActionCacheKey) -> Result<Vec<u8>, StagehandError> {
+        serde_json::to_vec(key).map_err(|e| StagehandError::Cache(e.to_string()))
+    }
+}
+
+#[cfg(feature = "sled-cache")]
+impl ActionCache for SledActionCache {
+    fn get(&self, key: &ActionCacheKey) -> Result<Option<Vec<ObservedAction>>, StagehandError> {
+        let value = self.tree.get(Self::encode_key(key)?).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        value
+            .map(|bytes| serde_json::from_slice(&bytes).map_err(|e| StagehandError::Cache(e.to_string())))
+            .transpose()
+    }
+
+    fn put(&self, key: &ActionCacheKey, actions: Vec<ObservedAction>) -> Result<(), StagehandError> {
+        let value = serde_json::to_vec(&actions).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        self.tree.insert(Self::encode_key(key)?, value).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        self.tree.flush().map_err(|e| StagehandError::Cache(e.to_string()))?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &ActionCacheKey) -> Result<(), StagehandError> {
+        self.tree.remove(Self::encode_key(key)?).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Extract Result Cache
+// =============================================================================
+
+/// Key identifying a cached extraction result
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractCacheKey {
+    /// Page URL without fragment
+    pub url: String,
+    pub instruction: String,
+    pub schema_hash: String,
+    pub selector: Option<String>,
+    /// Hash of the page content, when content checking is enabled
+    pub dom_hash: Option<String>,
+    /// URL without query string or fragment of the iframe the extract ran in, `None` for the
+    /// main frame
+    pub frame: Option<String>,
+}
+
+impl ExtractCacheKey {
+    /// Key for an extract on the page at `url`, in the iframe at `frame_url` if any
+    pub fn new(url: &str, instruction: &str, schema: &serde_json::Value, selector: Option<&str>, dom_hash: Option<String>, frame_url: Option<&str>) -> Self {
+        let end = url.find('#').unwrap_or(url.len());
+        Self {
+            url: url[..end].to_string(),
+            instruction: instruction.to_string(),
+            schema_hash: stable_hash(schema.to_string().as_bytes()),
+            selector: selector.map(str::to_string),
+            dom_hash,
+            frame: frame_url.map(url_pattern),
+        }
+    }
+}
+
+/// Hit/miss counters for an [`ExtractCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractCacheEntry {
+    key: ExtractCacheKey,
+    data_json: String,
+    /// Milliseconds since the Unix epoch
+    stored_at_ms: u64,
+}
+
+/// Opt-in cache of `extract` results, so repeated extractions on an unchanged page don't burn tokens.
+///
+/// By default entries are also keyed by a hash of the page content, fetched from the server with an
+/// instruction-less extract (no LLM call) before each lookup. Entries expire after `ttl`.
+#[derive(Debug)]
+pub struct ExtractCache {
+    ttl: Duration,
+    path: Option<PathBuf>,
+    check_page_content: bool,
+    entries: Mutex<HashMap<ExtractCacheKey, ExtractCacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ExtractCache {
+    /// In-memory cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            path: None,
+            check_page_content: true,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache persisted to a JSON file at `path`, starting empty if the file does not exist yet
+    pub fn open(path: impl AsRef<Path>, ttl: Duration) -> Result<Self, StagehandError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<ExtractCacheEntry>>(&bytes)
+                .map_err(|e| StagehandError::Cache(format!("Failed to parse {}: {}", path.display(), e)))?
+                .into_iter()
+                .map(|entry| (entry.key.clone(), entry))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(StagehandError::Cache(format!("Failed to read {}: {}", path.display(), e))),
+        };
+        Ok(Self { path: Some(path), entries: Mutex::new(entries), ..Self::new(ttl) })
+    }
+
+    /// Whether to key entries on a hash of the page content (default: true).
+    ///
+    /// Disabling this saves a round trip per `extract`, leaving the TTL as the only staleness check.
+    pub fn check_page_content(mut self, enabled: bool) -> Self {
+        self.check_page_content = enabled;
+        self
+    }
+
+    pub fn checks_page_content(&self) -> bool {
+        self.check_page_content
+    }
+
+    /// Look up a result, counting a hit or miss. Expired entries are dropped.
+    pub fn get(&self, key: &ExtractCacheKey) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        let fresh = entries
+            .get(key)
+            .filter(|entry| Duration::from_millis(unix_now_ms().saturating_sub(entry.stored_at_ms)) < self.ttl)
+            .map(|entry| entry.data_json.clone());
+
+        if fresh.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            entries.remove(key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        fresh
+    }
+
+    /// Store a result, rewriting the file of a persisted cache. `extract` calls this off the async
+    /// executor, and reports a failure as a log event instead of failing the extraction.
+    pub fn put(&self, key: ExtractCacheKey, data_json: String) -> Result<(), StagehandError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.clone(), ExtractCacheEntry { key, data_json, stored_at_ms: unix_now_ms() });
+        self.save(&entries)
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) -> Result<(), StagehandError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        self.save(&entries)
+    }
+
+    pub fn stats(&self) -> ExtractCacheStats {
+        ExtractCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn save(&self, entries: &HashMap<ExtractCacheKey, ExtractCacheEntry>) -> Result<(), StagehandError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut list: Vec<_> = entries.values().collect();
+        list.sort_by_key(|entry| entry.stored_at_ms);
+        let json = serde_json::to_vec_pretty(&list).map_err(|e| StagehandError::Cache(e.to_string()))?;
+        std::fs::write(path, json)
+            .map_err(|e| StagehandError::Cache(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+fn unix_now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
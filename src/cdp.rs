@@ -0,0 +1,116 @@
+//! Direct CDP control of the session's browser through chromiumoxide.
+//!
+//! Enabled with the `chromiumoxide` feature. Stagehand's AI methods and chromiumoxide operate on
+//! the same Browserbase browser, so deterministic CDP steps can be mixed with `act`/`extract`.
+
+use chromiumoxide::browser::Browser;
+use chromiumoxide::cdp::browser_protocol::target::{EventTargetCreated, EventTargetDestroyed, TargetId};
+use chromiumoxide::handler::Handler;
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::StagehandError;
+
+const CONNECT_ATTEMPTS: u32 = 3;
+const CONNECT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+impl From<chromiumoxide::error::CdpError> for StagehandError {
+    fn from(err: chromiumoxide::error::CdpError) -> Self {
+        StagehandError::Cdp(err.to_string())
+    }
+}
+
+/// A chromiumoxide `Browser` connected over CDP, with the task driving its event handler.
+///
+/// The handler task is aborted when this is dropped.
+pub struct CdpBrowser {
+    pub browser: Browser,
+    pub handler: JoinHandle<()>,
+    /// Open page targets, oldest first
+    page_targets: Arc<Mutex<Vec<TargetId>>>,
+    tracker: JoinHandle<()>,
+}
+
+impl CdpBrowser {
+    /// Connect to a CDP WebSocket URL, retrying briefly while a new session's browser starts
+    pub async fn connect(cdp_url: &str) -> Result<Self, StagehandError> {
+        let mut attempt = 1;
+        let (browser, handler) = loop {
+            match Browser::connect(cdp_url).await {
+                Ok(connected) => break connected,
+                Err(_) if attempt < CONNECT_ATTEMPTS => {
+                    attempt += 1;
+                    tokio::time::sleep(CONNECT_RETRY_DELAY).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        // Listen before the handler runs, so the targets it discovers on connect are seen too
+        let created = browser.event_listener::<EventTargetCreated>().await?;
+        let destroyed = browser.event_listener::<EventTargetDestroyed>().await?;
+        let handler = tokio::spawn(drive(handler));
+        let page_targets = Arc::new(Mutex::new(Vec::new()));
+        let tracker = tokio::spawn(track_pages(created, destroyed, page_targets.clone()));
+        Ok(Self { browser, handler, page_targets, tracker })
+    }
+
+    /// Whether the CDP connection has closed
+    pub fn is_closed(&self) -> bool {
+        self.handler.is_finished()
+    }
+
+    /// The most recently opened page that is still open, or a new blank page if the browser has
+    /// none. Pages opened by `act` (e.g. a link with `target=_blank`) become active, like in the
+    /// Stagehand server.
+    pub async fn active_page(&self) -> Result<Page, StagehandError> {
+        let newest_first: Vec<_> = self.page_targets.lock().unwrap().iter().rev().cloned().collect();
+        for target_id in newest_first {
+            // A target that is not attached yet is skipped until it is
+            if let Ok(page) = self.browser.get_page(target_id).await {
+                return Ok(page);
+            }
+        }
+        match self.browser.pages().await?.into_iter().next() {
+            Some(page) => Ok(page),
+            None => Ok(self.browser.new_page("about:blank").await?),
+        }
+    }
+}
+
+impl Drop for CdpBrowser {
+    fn drop(&mut self) {
+        self.handler.abort();
+        self.tracker.abort();
+    }
+}
+
+async fn track_pages(
+    mut created: chromiumoxide::listeners::EventStream<EventTargetCreated>,
+    mut destroyed: chromiumoxide::listeners::EventStream<EventTargetDestroyed>,
+    page_targets: Arc<Mutex<Vec<TargetId>>>,
+) {
+    loop {
+        tokio::select! {
+            Some(event) = created.next() => {
+                if event.target_info.r#type == "page" {
+                    page_targets.lock().unwrap().push(event.target_info.target_id.clone());
+                }
+            }
+            Some(event) = destroyed.next() => {
+                page_targets.lock().unwrap().retain(|id| *id != event.target_id);
+            }
+            else => break,
+        }
+    }
+}
+
+async fn drive(mut handler: Handler) {
+    while let Some(event) = handler.next().await {
+        if event.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Frame trees, for finding the `frame_id` to pass to `act`, `extract` and `observe`.
+
+use serde::{Deserialize, Serialize};
+
+/// A frame in a page, with its child frames
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameInfo {
+    /// CDP frame id, usable as `frame_id` in request options
+    pub id: String,
+    pub url: String,
+    /// Value of the frame's `name` attribute
+    #[serde(default)]
+    pub name: Option<String>,
+    /// `None` for the page's main frame
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub children: Vec<FrameInfo>,
+}
+
+impl FrameInfo {
+    /// This frame and all its descendants, depth first
+    pub fn iter(&self) -> impl Iterator<Item = &FrameInfo> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let frame = stack.pop()?;
+            stack.extend(frame.children.iter().rev());
+            Some(frame)
+        })
+    }
+
+    /// First frame (depth first) whose URL matches the glob `pattern`.
+    ///
+    /// `*` matches any run of characters and `?` a single character; the pattern must match the
+    /// whole URL, e.g. `"https://js.stripe.com/*"` or `"*checkout*"`.
+    pub fn find_by_url(&self, pattern: &str) -> Option<&FrameInfo> {
+        self.iter().find(|frame| glob_match(pattern, &frame.url))
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&FrameInfo> {
+        self.iter().find(|frame| frame.name.as_deref() == Some(name))
+    }
+}
+
+#[cfg(feature = "chromiumoxide")]
+impl From<&chromiumoxide::cdp::browser_protocol::page::FrameTree> for FrameInfo {
+    fn from(tree: &chromiumoxide::cdp::browser_protocol::page::FrameTree) -> Self {
+        FrameInfo {
+            id: tree.frame.id.inner().clone(),
+            url: format!("{}{}", tree.frame.url, tree.frame.url_fragment.as_deref().unwrap_or_default()),
+            name: tree.frame.name.clone().filter(|name| !name.is_empty()),
+            parent_id: tree.frame.parent_id.as_ref().map(|id| id.inner().clone()),
+            children: tree.child_frames.iter().flatten().map(FrameInfo::from).collect(),
+        }
+    }
+}
+
+/// Whole-string glob match supporting `*` and `?`
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if let Some((after_star, tried)) = star {
+            // Let the last `*` swallow one more character
+            p = after_star;
+            t = tried + 1;
+            star = Some((after_star, tried + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,143 @@
+//! Handing a session over to a human for steps automation can't handle, such as a CAPTCHA or a
+//! 2FA prompt.
+//!
+//! A [`HumanHandoff`] fetches the session's live debugger URL, passes it to a
+//! [`HandoffNotifier`] (Slack, email, a CLI prompt, ...) and waits until the human signals
+//! through [`HandoffCompletion`] or the timeout elapses.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::StagehandError;
+use crate::browserbase::{BrowserbaseClient, SessionLiveUrls};
+
+/// What a human needs to take over a session
+#[derive(Debug)]
+pub struct HandoffRequest {
+    pub session_id: String,
+    /// Why the automation needs help, e.g. "Enter the 2FA code"
+    pub reason: String,
+    pub live_urls: SessionLiveUrls,
+    /// Signals the waiting automation; dropping every clone without signalling aborts the handoff
+    pub completion: HandoffCompletion,
+}
+
+impl HandoffRequest {
+    /// Full-screen live view URL to send to the human
+    pub fn live_url(&self) -> &str {
+        &self.live_urls.debugger_fullscreen_url
+    }
+}
+
+/// Handle for signalling that a human is done with the session
+#[derive(Debug, Clone)]
+pub struct HandoffCompletion {
+    tx: async_channel::Sender<HandoffOutcome>,
+}
+
+impl HandoffCompletion {
+    /// Resume the automation
+    pub fn complete(&self) {
+        let _ = self.tx.try_send(HandoffOutcome::Completed);
+    }
+
+    /// Give up on the handoff, e.g. when the human declines
+    pub fn abort(&self, reason: impl Into<String>) {
+        let _ = self.tx.try_send(HandoffOutcome::Aborted(reason.into()));
+    }
+}
+
+/// How a handoff ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandoffOutcome {
+    Completed,
+    Aborted(String),
+    TimedOut,
+}
+
+/// Delivers a handoff request to a human.
+///
+/// `notify` should return once the request is delivered, keeping `request.completion` to signal
+/// later. Closures `Fn(HandoffRequest) -> Result<(), StagehandError>` implement this trait.
+#[async_trait]
+pub trait HandoffNotifier: Send + Sync {
+    async fn notify(&self, request: HandoffRequest) -> Result<(), StagehandError>;
+}
+
+#[async_trait]
+impl<F> HandoffNotifier for F
+where
+    F: Fn(HandoffRequest) -> Result<(), StagehandError> + Send + Sync,
+{
+    async fn notify(&self, request: HandoffRequest) -> Result<(), StagehandError> {
+        self(request)
+    }
+}
+
+/// Pauses automation until a human finishes with the session
+#[derive(Clone)]
+pub struct HumanHandoff {
+    notifier: Arc<dyn HandoffNotifier>,
+    timeout: Option<Duration>,
+}
+
+impl HumanHandoff {
+    pub fn new(notifier: impl HandoffNotifier + 'static) -> Self {
+        Self { notifier: Arc::new(notifier), timeout: None }
+    }
+
+    /// Stop waiting after `timeout` (no timeout by default)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Notify a human about `session_id` and wait for the outcome
+    pub async fn run(&self, browserbase: &BrowserbaseClient, session_id: &str, reason: impl Into<String>) -> Result<HandoffOutcome, StagehandError> {
+        let live_urls = browserbase.session_live_urls(session_id).await?;
+        let (tx, rx) = async_channel::bounded(1);
+        self.notifier.notify(HandoffRequest {
+            session_id: session_id.to_string(),
+            reason: reason.into(),
+            live_urls,
+            completion: HandoffCompletion { tx },
+        }).await?;
+
+        let outcome = async {
+            rx.recv().await.unwrap_or_else(|_| HandoffOutcome::Aborted("Handoff completion dropped without a signal".to_string()))
+        };
+        let Some(timeout) = self.timeout else {
+            return Ok(outcome.await);
+        };
+        futures::pin_mut!(outcome);
+        let timer = sleep(timeout);
+        futures::pin_mut!(timer);
+        Ok(match futures::future::select(outcome, timer).await {
+            futures::future::Either::Left((outcome, _)) => outcome,
+            futures::future::Either::Right(_) => HandoffOutcome::TimedOut,
+        })
+    }
+}
+
+// Sleep using the appropriate runtime
+#[cfg(feature = "tokio-runtime")]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+#[cfg(all(feature = "async-std-runtime", not(feature = "tokio-runtime")))]
+async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await
+}
+
+// Without a runtime, a thread wakes the timer. Handoffs are rare and long, so the thread is cheap.
+#[cfg(not(any(feature = "tokio-runtime", feature = "async-std-runtime")))]
+async fn sleep(duration: Duration) {
+    let (tx, rx) = futures::channel::oneshot::channel::<()>();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}
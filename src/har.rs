@@ -0,0 +1,503 @@
+//! HAR 1.2 capture of a page's network traffic.
+//!
+//! The [`Har`] types follow the [HAR 1.2 spec](http://www.softwareishard.com/blog/har-12-spec/)
+//! and load into browser devtools and HAR viewers. Capturing from a live page with
+//! [`HarCapture`] needs the `chromiumoxide` feature.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::StagehandError;
+
+/// A HAR document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+/// One request and its response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    /// ISO 8601 time the request started
+    pub started_date_time: String,
+    /// Total time in milliseconds
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: HarCache,
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", default, skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    /// CDP resource type, e.g. `Document`, `XHR`, `Image`
+    #[serde(rename = "_resourceType", default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    /// Network error for requests that failed, e.g. `net::ERR_NAME_NOT_RESOLVED`
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    /// -1 when unknown
+    pub headers_size: i64,
+    /// -1 when unknown
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    /// 0 for requests that failed before a response
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    /// -1 when unknown
+    pub headers_size: i64,
+    /// -1 when unknown
+    pub body_size: i64,
+}
+
+/// A header, cookie or query parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    /// Response body, present when captured with `content(true)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` for binary bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarCache {}
+
+/// Phases of a request in milliseconds; -1 means the phase does not apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub blocked: f64,
+    pub dns: f64,
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    pub ssl: f64,
+}
+
+impl Har {
+    /// An empty HAR 1.2 log created by this SDK
+    pub fn new(entries: Vec<HarEntry>) -> Self {
+        Self {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator { name: env!("CARGO_PKG_NAME").to_string(), version: env!("CARGO_PKG_VERSION").to_string() },
+                entries,
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, StagehandError> {
+        serde_json::to_string_pretty(self).map_err(|e| StagehandError::Api(format!("Failed to serialize HAR: {}", e)))
+    }
+
+    /// Write the HAR as JSON, e.g. to `trace.har`
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), StagehandError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Options for `start_har_capture`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct HarCaptureOptions {
+    /// Include response bodies in `content.text` (base64 for binary bodies)
+    pub content: bool,
+}
+
+impl HarCaptureOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn content(mut self, content: bool) -> Self {
+        self.content = content;
+        self
+    }
+}
+
+#[cfg(feature = "chromiumoxide")]
+pub use capture::HarCapture;
+
+#[cfg(feature = "chromiumoxide")]
+mod capture {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use chromiumoxide::cdp::browser_protocol::network::{
+        EnableParams, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+        GetResponseBodyParams, Request, Response,
+    };
+    use chromiumoxide::cdp::browser_protocol::browser::GetVersionParams;
+    use futures::channel::oneshot;
+    use futures::future::Either;
+    use futures::{FutureExt, Stream, StreamExt};
+    use std::collections::HashMap;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use tokio::task::JoinHandle;
+
+    use super::*;
+
+    /// ISO 8601 UTC timestamp for seconds since the Unix epoch
+    fn iso8601(epoch_seconds: f64) -> String {
+        let millis = (epoch_seconds * 1000.0).round() as i64;
+        let (days, ms_of_day) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+
+        // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year, month, day,
+            ms_of_day / 3_600_000, ms_of_day / 60_000 % 60, ms_of_day / 1000 % 60, ms_of_day % 1000,
+        )
+    }
+
+    /// `HTTP/2` style version for a CDP protocol name such as `h2`
+    fn http_version(protocol: Option<&str>) -> String {
+        match protocol {
+            Some("h2") => "HTTP/2".to_string(),
+            Some("h3") => "HTTP/3".to_string(),
+            Some(protocol) if !protocol.is_empty() => protocol.to_uppercase(),
+            _ => "HTTP/1.1".to_string(),
+        }
+    }
+
+    /// CDP headers object (multiple values joined by newlines) as HAR name/value pairs
+    fn header_pairs(headers: &serde_json::Value) -> Vec<HarNameValue> {
+        let Some(headers) = headers.as_object() else {
+            return Vec::new();
+        };
+        headers.iter()
+            .flat_map(|(name, value)| {
+                let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                value.split('\n').map(|v| HarNameValue { name: name.clone(), value: v.to_string() }).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn header_value<'a>(headers: &'a [HarNameValue], name: &str) -> Option<&'a str> {
+        headers.iter().find(|h| h.name.eq_ignore_ascii_case(name)).map(|h| h.value.as_str())
+    }
+
+    /// Cookies from `Cookie` request headers (`a=1; b=2`) or `Set-Cookie` response headers
+    fn cookies(headers: &[HarNameValue], header: &str) -> Vec<HarNameValue> {
+        // In `Set-Cookie`, attributes after the first `;` describe the cookie rather than being cookies
+        let max_pairs = if header.eq_ignore_ascii_case("cookie") { usize::MAX } else { 1 };
+        headers.iter()
+            .filter(|h| h.name.eq_ignore_ascii_case(header))
+            .flat_map(|h| h.value.split(';').take(max_pairs))
+            .filter_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                Some(HarNameValue { name: name.to_string(), value: value.to_string() })
+            })
+            .collect()
+    }
+
+    fn query_string(url: &str) -> Vec<HarNameValue> {
+        reqwest::Url::parse(url)
+            .map(|url| url.query_pairs().map(|(name, value)| HarNameValue { name: name.into_owned(), value: value.into_owned() }).collect())
+            .unwrap_or_default()
+    }
+
+    enum NetworkEvent {
+        RequestWillBeSent(Arc<EventRequestWillBeSent>),
+        ResponseReceived(Arc<EventResponseReceived>),
+        LoadingFinished(Arc<EventLoadingFinished>),
+        LoadingFailed(Arc<EventLoadingFailed>),
+    }
+
+    /// A request in flight, or finished and waiting for `stop`
+    struct PendingEntry {
+        wall_time: f64,
+        /// CDP monotonic time in seconds
+        start: f64,
+        end: Option<f64>,
+        request: Request,
+        resource_type: Option<String>,
+        response: Option<Response>,
+        body: Option<(String, bool)>,
+        error: Option<String>,
+    }
+
+    #[derive(Default)]
+    struct CaptureState {
+        pending: HashMap<String, PendingEntry>,
+        done: Vec<PendingEntry>,
+    }
+
+    /// Records a page's network traffic until [`stop`](Self::stop) builds the [`Har`]
+    #[derive(Debug)]
+    pub struct HarCapture {
+        task: JoinHandle<()>,
+        stop: Option<oneshot::Sender<()>>,
+        state: Arc<Mutex<CaptureState>>,
+    }
+
+    impl std::fmt::Debug for CaptureState {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("CaptureState").field("pending", &self.pending.len()).field("done", &self.done.len()).finish()
+        }
+    }
+
+    impl HarCapture {
+        pub async fn start(page: &chromiumoxide::Page, options: HarCaptureOptions) -> Result<Self, StagehandError> {
+            let events: Vec<Pin<Box<dyn Stream<Item = NetworkEvent> + Send>>> = vec![
+                Box::pin(page.event_listener::<EventRequestWillBeSent>().await?.map(NetworkEvent::RequestWillBeSent)),
+                Box::pin(page.event_listener::<EventResponseReceived>().await?.map(NetworkEvent::ResponseReceived)),
+                Box::pin(page.event_listener::<EventLoadingFinished>().await?.map(NetworkEvent::LoadingFinished)),
+                Box::pin(page.event_listener::<EventLoadingFailed>().await?.map(NetworkEvent::LoadingFailed)),
+            ];
+            page.execute(EnableParams::default()).await?;
+
+            let state = Arc::new(Mutex::new(CaptureState::default()));
+            let (stop, mut stopped) = oneshot::channel();
+            let task = {
+                let page = page.clone();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let mut events = futures::stream::select_all(events);
+                    loop {
+                        match futures::future::select(events.next(), &mut stopped).await {
+                            Either::Left((Some(event), _)) => handle(&page, &state, event, options.content).await,
+                            Either::Left((None, _)) => return,
+                            Either::Right(_) => break,
+                        }
+                    }
+                    // CDP delivers messages in order, so once this round trip answers, every event sent
+                    // before `stop` is queued in the listeners
+                    let _ = page.execute(GetVersionParams::default()).await;
+                    while let Some(Some(event)) = events.next().now_or_never() {
+                        handle(&page, &state, event, options.content).await;
+                    }
+                })
+            };
+            Ok(Self { task, stop: Some(stop), state })
+        }
+
+        /// Stop recording and build the HAR from requests that got a response or failed.
+        ///
+        /// Network events the browser sent before the call are still recorded.
+        pub async fn stop(mut self) -> Har {
+            if let Some(stop) = self.stop.take() {
+                let _ = stop.send(());
+            }
+            let _ = (&mut self.task).await;
+            let mut state = self.state.lock().unwrap();
+            let state = &mut *state;
+            let mut entries: Vec<_> = state.done.drain(..).chain(state.pending.drain().map(|(_, entry)| entry)).collect();
+            entries.sort_by(|a, b| a.wall_time.total_cmp(&b.wall_time));
+            Har::new(entries.into_iter().filter_map(har_entry).collect())
+        }
+    }
+
+    impl Drop for HarCapture {
+        fn drop(&mut self) {
+            self.task.abort();
+        }
+    }
+
+    async fn handle(page: &chromiumoxide::Page, state: &Mutex<CaptureState>, event: NetworkEvent, content: bool) {
+        match event {
+            NetworkEvent::RequestWillBeSent(event) => {
+                let mut state = state.lock().unwrap();
+                let id = event.request_id.inner().clone();
+                // A redirect reuses the request id; the redirect response completes the previous hop
+                if let Some(redirect) = &event.redirect_response
+                    && let Some(mut previous) = state.pending.remove(&id)
+                {
+                    previous.response = Some(redirect.clone());
+                    previous.end = Some(*event.timestamp.inner());
+                    state.done.push(previous);
+                }
+                state.pending.insert(id, PendingEntry {
+                    wall_time: *event.wall_time.inner(),
+                    start: *event.timestamp.inner(),
+                    end: None,
+                    request: event.request.clone(),
+                    resource_type: event.r#type.as_ref().map(|t| t.as_ref().to_string()),
+                    response: None,
+                    body: None,
+                    error: None,
+                });
+            }
+            NetworkEvent::ResponseReceived(event) => {
+                if let Some(entry) = state.lock().unwrap().pending.get_mut(event.request_id.inner()) {
+                    entry.response = Some(event.response.clone());
+                }
+            }
+            NetworkEvent::LoadingFinished(event) => {
+                let body = if content {
+                    page.execute(GetResponseBodyParams::new(event.request_id.clone())).await.ok()
+                        .map(|body| (body.result.body, body.result.base64_encoded))
+                } else {
+                    None
+                };
+                if let Some(entry) = state.lock().unwrap().pending.get_mut(event.request_id.inner()) {
+                    entry.end = Some(*event.timestamp.inner());
+                    entry.body = body;
+                }
+            }
+            NetworkEvent::LoadingFailed(event) => {
+                if let Some(entry) = state.lock().unwrap().pending.get_mut(event.request_id.inner()) {
+                    entry.end = Some(*event.timestamp.inner());
+                    entry.error = Some(event.error_text.clone());
+                }
+            }
+        }
+    }
+
+    fn har_entry(entry: PendingEntry) -> Option<HarEntry> {
+        if entry.response.is_none() && entry.error.is_none() {
+            return None;
+        }
+        let response = entry.response.as_ref();
+        let version = http_version(response.and_then(|r| r.protocol.as_deref()));
+        let total = entry.end.map_or(0.0, |end| ((end - entry.start) * 1000.0).max(0.0));
+        let timings = timings(&entry, total);
+
+        let request_headers = header_pairs(entry.request.headers.inner());
+        let post_data = entry.request.post_data_entries.as_ref().map(|entries| {
+            let bytes: Vec<u8> = entries.iter()
+                .filter_map(|e| e.bytes.as_ref())
+                .filter_map(|b| BASE64.decode(AsRef::<str>::as_ref(b)).ok())
+                .flatten()
+                .collect();
+            HarPostData {
+                mime_type: header_value(&request_headers, "content-type").unwrap_or_default().to_string(),
+                text: String::from_utf8_lossy(&bytes).into_owned(),
+            }
+        });
+        let request = HarRequest {
+            method: entry.request.method.clone(),
+            url: entry.request.url.clone(),
+            http_version: version.clone(),
+            cookies: cookies(&request_headers, "cookie"),
+            query_string: query_string(&entry.request.url),
+            body_size: post_data.as_ref().map_or(0, |p| p.text.len() as i64),
+            post_data,
+            headers: request_headers,
+            headers_size: -1,
+        };
+
+        let response_headers = response.map(|r| header_pairs(r.headers.inner())).unwrap_or_default();
+        let (text, encoding) = match entry.body {
+            Some((body, true)) => (Some(body), Some("base64".to_string())),
+            Some((body, false)) => (Some(body), None),
+            None => (None, None),
+        };
+        let size = match (&text, &encoding) {
+            (Some(text), Some(_)) => BASE64.decode(text).map_or(-1, |b| b.len() as i64),
+            (Some(text), None) => text.len() as i64,
+            _ => response.map_or(0, |r| r.encoded_data_length as i64),
+        };
+        let response = HarResponse {
+            status: response.map_or(0, |r| r.status),
+            status_text: response.map(|r| r.status_text.clone()).or(entry.error.clone()).unwrap_or_default(),
+            http_version: version,
+            cookies: cookies(&response_headers, "set-cookie"),
+            content: HarContent {
+                size,
+                mime_type: response.map(|r| r.mime_type.clone()).unwrap_or_default(),
+                text,
+                encoding,
+            },
+            redirect_url: header_value(&response_headers, "location").unwrap_or_default().to_string(),
+            headers: response_headers,
+            headers_size: -1,
+            body_size: -1,
+        };
+
+        Some(HarEntry {
+            started_date_time: iso8601(entry.wall_time),
+            time: total,
+            request,
+            response,
+            cache: HarCache::default(),
+            timings,
+            server_ip_address: entry.response.as_ref().and_then(|r| r.remote_ip_address.clone()),
+            resource_type: entry.resource_type,
+            error: entry.error,
+        })
+    }
+
+    /// HAR phases from CDP resource timing, whose offsets are milliseconds from `request_time`
+    fn timings(entry: &PendingEntry, total: f64) -> HarTimings {
+        let Some(t) = entry.response.as_ref().and_then(|r| r.timing.as_ref()) else {
+            return HarTimings { blocked: -1.0, dns: -1.0, connect: -1.0, send: 0.0, wait: total, receive: 0.0, ssl: -1.0 };
+        };
+        let phase = |start: f64, end: f64| if start >= 0.0 { end - start } else { -1.0 };
+        let blocked = [t.dns_start, t.connect_start, t.send_start].into_iter().find(|s| *s >= 0.0).unwrap_or(-1.0);
+        let headers_end = t.request_time + t.receive_headers_end / 1000.0;
+        HarTimings {
+            blocked,
+            dns: phase(t.dns_start, t.dns_end),
+            connect: phase(t.connect_start, t.connect_end),
+            send: (t.send_end - t.send_start).max(0.0),
+            wait: (t.receive_headers_end - t.send_end).max(0.0),
+            receive: entry.end.map_or(0.0, |end| ((end - headers_end) * 1000.0).max(0.0)),
+            ssl: phase(t.ssl_start, t.ssl_end),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Request interception and mocking through the CDP `Fetch` domain.
+//!
+//! Enabled with the `chromiumoxide` feature. Build a [`NetworkInterceptor`] from rules, then
+//! attach it to a page with [`Page::intercept_network`](crate::Page::intercept_network) or
+//! [`Stagehand::intercept_network`](crate::Stagehand::intercept_network). Rules are checked in
+//! the order they were added and the first match wins.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, DisableParams, EnableParams, EventRequestPaused, FailRequestParams, FulfillRequestParams,
+    GetResponseBodyParams, HeaderEntry, RequestPattern, RequestStage,
+};
+use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+pub use chromiumoxide::cdp::browser_protocol::network::ResourceType;
+use futures::StreamExt;
+use serde::Serialize;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::StagehandError;
+use crate::frames::glob_match;
+
+/// Transforms a response body before the page sees it. Receives the request URL and the
+/// original body.
+pub type ResponseRewriter = Arc<dyn Fn(&str, Vec<u8>) -> Vec<u8> + Send + Sync>;
+
+/// A canned response served instead of hitting the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self { status, headers: Vec::new(), body: body.into() }
+    }
+
+    /// A `200` response with `value` serialized as JSON
+    pub fn json(value: &serde_json::Value) -> Self {
+        Self::new(200, value.to_string()).header("Content-Type", "application/json")
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// What the interceptor does with a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InterceptDecision {
+    /// Let the request through untouched
+    Continue,
+    /// Fail the request with `net::ERR_BLOCKED_BY_CLIENT`
+    Block,
+    /// Serve a [`MockResponse`]
+    Fulfill,
+    /// Let the request through and rewrite its response body
+    RewriteResponse,
+}
+
+/// A request seen by an interceptor with `record_requests(true)`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRequest {
+    pub url: String,
+    pub method: String,
+    pub resource_type: ResourceType,
+    pub headers: serde_json::Value,
+    pub decision: InterceptDecision,
+}
+
+#[derive(Clone)]
+enum RuleAction {
+    Block,
+    Fulfill(MockResponse),
+    Rewrite(ResponseRewriter),
+}
+
+#[derive(Clone)]
+struct InterceptRule {
+    /// Glob over the request URL; `None` matches any URL
+    url_pattern: Option<String>,
+    /// Empty matches any resource type
+    resource_types: Vec<ResourceType>,
+    action: RuleAction,
+}
+
+impl InterceptRule {
+    fn matches(&self, url: &str, resource_type: &ResourceType) -> bool {
+        self.url_pattern.as_deref().is_none_or(|pattern| glob_match(pattern, url))
+            && (self.resource_types.is_empty() || self.resource_types.contains(resource_type))
+    }
+}
+
+/// Rules for blocking, mocking, rewriting and recording a page's requests.
+///
+/// ```ignore
+/// let handle = stagehand.intercept_network(
+///     NetworkInterceptor::new()
+///         .block_resource_types([ResourceType::Image, ResourceType::Font])
+///         .fulfill("*/api/prices*", MockResponse::json(&json!({ "price": 10 })))
+///         .rewrite_response("*/config.js", |_, body| String::from_utf8_lossy(&body).replace("prod", "test").into())
+///         .record_requests(true),
+/// ).await?;
+/// ```
+#[derive(Clone, Default)]
+pub struct NetworkInterceptor {
+    rules: Vec<InterceptRule>,
+    record: bool,
+}
+
+impl fmt::Debug for NetworkInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NetworkInterceptor")
+            .field("rules", &self.rules.len())
+            .field("record", &self.record)
+            .finish()
+    }
+}
+
+impl NetworkInterceptor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block every request of these resource types (e.g. images, fonts, media)
+    pub fn block_resource_types(mut self, types: impl IntoIterator<Item = ResourceType>) -> Self {
+        self.rules.push(InterceptRule {
+            url_pattern: None,
+            resource_types: types.into_iter().collect(),
+            action: RuleAction::Block,
+        });
+        self
+    }
+
+    /// Block requests whose URL matches the glob `pattern` (`*` and `?` wildcards)
+    pub fn block_url(mut self, pattern: impl Into<String>) -> Self {
+        self.rules.push(InterceptRule { url_pattern: Some(pattern.into()), resource_types: Vec::new(), action: RuleAction::Block });
+        self
+    }
+
+    /// Serve `response` for requests whose URL matches `pattern`, without hitting the network
+    pub fn fulfill(mut self, pattern: impl Into<String>, response: MockResponse) -> Self {
+        self.rules.push(InterceptRule {
+            url_pattern: Some(pattern.into()),
+            resource_types: Vec::new(),
+            action: RuleAction::Fulfill(response),
+        });
+        self
+    }
+
+    /// Rewrite the response body of requests whose URL matches `pattern`
+    pub fn rewrite_response<F>(mut self, pattern: impl Into<String>, rewrite: F) -> Self
+    where
+        F: Fn(&str, Vec<u8>) -> Vec<u8> + Send + Sync + 'static,
+    {
+        self.rules.push(InterceptRule {
+            url_pattern: Some(pattern.into()),
+            resource_types: Vec::new(),
+            action: RuleAction::Rewrite(Arc::new(rewrite)),
+        });
+        self
+    }
+
+    /// Keep a log of every request the page makes, available from
+    /// [`InterceptorHandle::recorded_requests`]
+    pub fn record_requests(mut self, record: bool) -> Self {
+        self.record = record;
+        self
+    }
+
+    /// What this interceptor does with a request for `url` of `resource_type`
+    pub fn decide(&self, url: &str, resource_type: &ResourceType) -> InterceptDecision {
+        match self.rule_for(url, resource_type).map(|rule| &rule.action) {
+            None => InterceptDecision::Continue,
+            Some(RuleAction::Block) => InterceptDecision::Block,
+            Some(RuleAction::Fulfill(_)) => InterceptDecision::Fulfill,
+            Some(RuleAction::Rewrite(_)) => InterceptDecision::RewriteResponse,
+        }
+    }
+
+    fn rule_for(&self, url: &str, resource_type: &ResourceType) -> Option<&InterceptRule> {
+        self.rules.iter().find(|rule| rule.matches(url, resource_type))
+    }
+
+    /// `Fetch.enable` patterns: every request when recording or blocking/mocking, plus the
+    /// response stage of rewritten URLs
+    fn patterns(&self) -> Vec<RequestPattern> {
+        let intercepts_requests = self.record || self.rules.iter().any(|rule| !matches!(rule.action, RuleAction::Rewrite(_)));
+        let mut patterns = Vec::new();
+        if intercepts_requests {
+            patterns.push(RequestPattern { url_pattern: Some("*".into()), resource_type: None, request_stage: Some(RequestStage::Request) });
+        }
+        for rule in &self.rules {
+            if let RuleAction::Rewrite(_) = rule.action {
+                patterns.push(RequestPattern {
+                    url_pattern: rule.url_pattern.clone(),
+                    resource_type: None,
+                    request_stage: Some(RequestStage::Response),
+                });
+            }
+        }
+        patterns
+    }
+
+    /// Start intercepting requests of `page` until the returned handle is detached or dropped.
+    /// Dropping the handle disables interception from a background task, so requests made
+    /// right after the drop may still be paused briefly.
+    pub async fn attach(self, page: &chromiumoxide::Page) -> Result<InterceptorHandle, StagehandError> {
+        let mut events = page.event_listener::<EventRequestPaused>().await?;
+        page.execute(EnableParams { patterns: Some(self.patterns()), handle_auth_requests: None }).await?;
+
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let task = {
+            let page = page.clone();
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                while let Some(event) = events.next().await {
+                    // A failed command means the page went away; the request is dropped with it
+                    let _ = self.handle(&page, &event, &recorded).await;
+                }
+            })
+        };
+
+        Ok(InterceptorHandle { page: page.clone(), task, recorded, disabled: false })
+    }
+
+    async fn handle(
+        &self,
+        page: &chromiumoxide::Page,
+        event: &EventRequestPaused,
+        recorded: &Mutex<Vec<RecordedRequest>>,
+    ) -> Result<(), StagehandError> {
+        let url = &event.request.url;
+
+        // Response stage: only rewrite rules ask for it
+        if event.response_status_code.is_some() || event.response_error_reason.is_some() {
+            if event.response_error_reason.is_none()
+                && let Some(RuleAction::Rewrite(rewrite)) = self.rule_for(url, &event.resource_type).map(|rule| &rule.action)
+            {
+                match self.rewritten(page, event, rewrite).await {
+                    Ok(params) => {
+                        page.execute(params).await?;
+                    }
+                    // Without the original body there is nothing to rewrite
+                    Err(_) => {
+                        page.execute(ContinueRequestParams::new(event.request_id.clone())).await?;
+                    }
+                }
+            } else {
+                page.execute(ContinueRequestParams::new(event.request_id.clone())).await?;
+            }
+            return Ok(());
+        }
+
+        let decision = self.decide(url, &event.resource_type);
+        if self.record {
+            recorded.lock().unwrap().push(RecordedRequest {
+                url: url.clone(),
+                method: event.request.method.clone(),
+                resource_type: event.resource_type.clone(),
+                headers: event.request.headers.inner().clone(),
+                decision: decision.clone(),
+            });
+        }
+
+        match self.rule_for(url, &event.resource_type).map(|rule| &rule.action) {
+            Some(RuleAction::Block) => {
+                page.execute(FailRequestParams::new(event.request_id.clone(), ErrorReason::BlockedByClient)).await?;
+            }
+            Some(RuleAction::Fulfill(response)) => {
+                let mut params = FulfillRequestParams::new(event.request_id.clone(), response.status as i64);
+                params.response_headers = Some(header_entries(&response.headers));
+                params.body = Some(BASE64.encode(&response.body).into());
+                page.execute(params).await?;
+            }
+            Some(RuleAction::Rewrite(_)) | None => {
+                page.execute(ContinueRequestParams::new(event.request_id.clone())).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn rewritten(
+        &self,
+        page: &chromiumoxide::Page,
+        event: &EventRequestPaused,
+        rewrite: &ResponseRewriter,
+    ) -> Result<FulfillRequestParams, StagehandError> {
+        let body = page.execute(GetResponseBodyParams::new(event.request_id.clone())).await?.result;
+        let original = if body.base64_encoded {
+            BASE64.decode(&body.body).map_err(|e| StagehandError::Cdp(format!("Invalid response body: {}", e)))?
+        } else {
+            body.body.into_bytes()
+        };
+        let rewritten = rewrite(&event.request.url, original);
+
+        // The body changes length and is no longer compressed
+        let headers = event.response_headers.iter().flatten()
+            .filter(|h| !h.name.eq_ignore_ascii_case("content-length") && !h.name.eq_ignore_ascii_case("content-encoding"))
+            .cloned()
+            .collect();
+        let mut params = FulfillRequestParams::new(event.request_id.clone(), event.response_status_code.unwrap_or(200));
+        params.response_headers = Some(headers);
+        params.body = Some(BASE64.encode(rewritten).into());
+        Ok(params)
+    }
+}
+
+fn header_entries(headers: &[(String, String)]) -> Vec<HeaderEntry> {
+    headers.iter().map(|(name, value)| HeaderEntry::new(name.clone(), value.clone())).collect()
+}
+
+/// An interceptor attached to a page. Dropping the handle turns interception off in the
+/// background; call [`detach`](Self::detach) to wait for that and see any error.
+#[derive(Debug)]
+pub struct InterceptorHandle {
+    page: chromiumoxide::Page,
+    task: JoinHandle<()>,
+    recorded: Arc<Mutex<Vec<RecordedRequest>>>,
+    /// Set once `Fetch.disable` was sent, so dropping the handle doesn't send it again
+    disabled: bool,
+}
+
+impl InterceptorHandle {
+    /// Requests seen so far, oldest first (empty unless `record_requests(true)`)
+    pub fn recorded_requests(&self) -> Vec<RecordedRequest> {
+        self.recorded.lock().unwrap().clone()
+    }
+
+    /// Stop intercepting and let requests through normally again
+    pub async fn detach(mut self) -> Result<Vec<RecordedRequest>, StagehandError> {
+        self.task.abort();
+        self.disabled = true;
+        self.page.execute(DisableParams::default()).await?;
+        Ok(self.recorded_requests())
+    }
+}
+
+impl Drop for InterceptorHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+        if self.disabled {
+            return;
+        }
+        // Without the task nothing continues paused requests, so the page would hang on its next
+        // request. Outside a runtime there is no way to send the command; the page is usually
+        // being torn down then anyway.
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let page = self.page.clone();
+            runtime.spawn(async move {
+                let _ = page.execute(DisableParams::default()).await;
+            });
+        }
+    }
+}
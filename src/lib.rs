@@ -10,6 +10,61 @@ use std::fmt;
 use reqwest::Client;
 use eventsource_client::{Client as SseClient, ClientBuilder, SSE};
 
+pub mod accessibility;
+pub mod browserbase;
+pub mod cache;
+#[cfg(feature = "chromiumoxide")]
+pub mod cdp;
+pub mod frames;
+#[cfg(feature = "chromiumoxide")]
+pub mod locator;
+#[cfg(feature = "chromiumoxide")]
+pub mod page;
+pub mod page_events;
+pub mod pdf;
+pub mod handoff;
+pub mod har;
+#[cfg(feature = "chromiumoxide")]
+pub mod interceptor;
+pub mod recording;
+pub mod storage;
+pub mod variables;
+#[cfg(feature = "chromiumoxide")]
+pub mod wait;
+
+pub use accessibility::AccessibilityNode;
+pub use browserbase::{BrowserbaseClient, BrowserbaseContext, DownloadedFile, LivePage, SESSION_UPLOADS_DIR, Session, SessionLiveUrls, SessionLog, SessionStatus, SessionUpdateStatus};
+pub use browserbase::{
+    BrowserContextSettings, BrowserSettings, BrowserbaseSessionCreateParams, Fingerprint, FingerprintBrowser,
+    FingerprintDevice, FingerprintOperatingSystem, FingerprintScreen, ProxyConfig, ProxyGeolocation, Proxies, Region,
+    Viewport,
+};
+#[cfg(feature = "chromiumoxide")]
+pub use cdp::CdpBrowser;
+#[cfg(feature = "chromiumoxide")]
+pub use locator::{DEFAULT_LOCATOR_TIMEOUT, Locator, Selector};
+#[cfg(feature = "chromiumoxide")]
+pub use page::{ClipRect, ImageFormat, Page, ScreenshotOptions};
+pub use pdf::PdfOptions;
+#[cfg(feature = "pdf-text")]
+pub use pdf::pdf_text;
+pub use page_events::{ConsoleMessage, FailedRequest, PageEvent, PageException};
+#[cfg(feature = "chromiumoxide")]
+pub use page_events::{PageEventCapture, PageEventStream};
+pub use cache::{ActionCache, ActionCacheKey, ExtractCache, ExtractCacheKey, ExtractCacheStats, InMemoryActionCache, JsonFileActionCache};
+#[cfg(feature = "sled-cache")]
+pub use cache::SledActionCache;
+pub use frames::FrameInfo;
+#[cfg(feature = "chromiumoxide")]
+pub use interceptor::{InterceptDecision, InterceptorHandle, MockResponse, NetworkInterceptor, RecordedRequest, ResourceType, ResponseRewriter};
+pub use har::{Har, HarCache, HarCaptureOptions, HarContent, HarCreator, HarEntry, HarLog, HarNameValue, HarPostData, HarRequest, HarResponse, HarTimings};
+#[cfg(feature = "chromiumoxide")]
+pub use har::HarCapture;
+pub use handoff::{HandoffCompletion, HandoffNotifier, HandoffOutcome, HandoffRequest, HumanHandoff};
+pub use recording::{RecordingEvent, cdn_replay_html, write_cdn_replay_html};
+pub use storage::{OriginStorage, SameSite, StorageCookie, StorageItem, StorageState};
+pub use variables::{SecretString, VariableValue};
+
 // =============================================================================
 // Constants
 // =============================================================================
@@ -57,6 +112,8 @@ pub struct StartResponse {
 #[derive(Debug, Clone)]
 pub enum ActResponseEvent {
     Log(LogLine),
+    /// Actions the server performed, emitted just before `Success`
+    Actions(Vec<ObservedAction>),
     Success(bool),
 }
 
@@ -118,6 +175,61 @@ pub struct NavigateResponse {
     pub event: Option<NavigateResponseEvent>,
 }
 
+// =============================================================================
+// Action Types (matching V3 API schema)
+// =============================================================================
+
+/// An action suggested by `observe`, which can be passed back to `act` to run it
+/// without another round of LLM inference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservedAction {
+    pub selector: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_node_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<String>,
+}
+
+/// Input for the act endpoint - either a natural language instruction or an observed action
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ActInput {
+    Instruction(String),
+    Action(ObservedAction),
+}
+
+impl From<String> for ActInput {
+    fn from(instruction: String) -> Self {
+        ActInput::Instruction(instruction)
+    }
+}
+
+impl From<&str> for ActInput {
+    fn from(instruction: &str) -> Self {
+        ActInput::Instruction(instruction.to_string())
+    }
+}
+
+impl From<ObservedAction> for ActInput {
+    fn from(action: ObservedAction) -> Self {
+        ActInput::Action(action)
+    }
+}
+
+/// File input targeted by `upload_file`. There is deliberately no conversion from strings, as a
+/// selector and a description can't be told apart reliably.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadTarget {
+    /// A CSS or XPath selector for the input
+    Selector(String),
+    /// Natural language description of the input, resolved with `observe`
+    Instruction(String),
+}
+
 // =============================================================================
 // Model Configuration Types (matches API exactly)
 // =============================================================================
@@ -201,11 +313,11 @@ pub enum Env {
     Browserbase,
 }
 
-impl ToString for Env {
-    fn to_string(&self) -> String {
+impl fmt::Display for Env {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Env::Local => "LOCAL".to_string(),
-            Env::Browserbase => "BROWSERBASE".to_string(),
+            Env::Local => write!(f, "LOCAL"),
+            Env::Browserbase => write!(f, "BROWSERBASE"),
         }
     }
 }
@@ -234,6 +346,15 @@ impl From<Model> for ModelConfiguration {
     }
 }
 
+impl From<Model> for ModelObj {
+    fn from(m: Model) -> Self {
+        match m {
+            Model::String(model_name) => ModelObj { model_name, api_key: None, base_url: None },
+            Model::Config { model_name, api_key, base_url } => ModelObj { model_name, api_key, base_url },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LocalBrowserLaunchOptions {
     pub headless: Option<bool>,
@@ -252,7 +373,7 @@ pub struct V3Options {
     pub api_key: Option<String>,
     pub project_id: Option<String>,
     pub browserbase_session_id: Option<String>,
-    pub browserbase_session_create_params: Option<serde_json::Value>,
+    pub browserbase_session_create_params: Option<BrowserbaseSessionCreateParams>,
     pub local_browser_launch_options: Option<LocalBrowserLaunchOptions>,
     pub model: Option<Model>,
     pub system_prompt: Option<String>,
@@ -262,6 +383,201 @@ pub struct V3Options {
     pub dom_settle_timeout_ms: Option<u32>,
     pub act_timeout_ms: Option<u32>,
     pub verbose: Option<i32>,
+    /// Cookies and web storage loaded into the browser once the session starts; needs the
+    /// `chromiumoxide` feature. If they can't be loaded, `start` ends the session and fails.
+    pub storage_state: Option<StorageState>,
+}
+
+impl V3Options {
+    /// Start the Browserbase session with the context `context_id` loaded.
+    ///
+    /// With `persist`, cookies and storage from the session are saved back to the context when it
+    /// ends, so later sessions can reuse a login. Create and delete contexts with
+    /// [`BrowserbaseClient::create_context`] and [`BrowserbaseClient::delete_context`].
+    pub fn with_browserbase_context(mut self, context_id: impl Into<String>, persist: bool) -> Self {
+        self.browserbase_session_create_params = Some(
+            self.browserbase_session_create_params.unwrap_or_default().with_context(context_id, persist),
+        );
+        self
+    }
+}
+
+// =============================================================================
+// Request Options (matching V3 API schema)
+// =============================================================================
+
+/// When navigation is considered finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadState {
+    Load,
+    #[serde(rename = "domcontentloaded")]
+    DomContentLoaded,
+    #[serde(rename = "networkidle")]
+    NetworkIdle,
+}
+
+/// Options for `navigate`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct NavigateOptions {
+    pub referer: Option<String>,
+    /// Timeout in milliseconds
+    pub timeout: Option<u32>,
+    pub wait_until: Option<LoadState>,
+    pub frame_id: Option<String>,
+}
+
+impl NavigateOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn referer(mut self, referer: impl Into<String>) -> Self {
+        self.referer = Some(referer.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout = Some(timeout_ms);
+        self
+    }
+
+    pub fn wait_until(mut self, state: LoadState) -> Self {
+        self.wait_until = Some(state);
+        self
+    }
+
+    pub fn frame_id(mut self, frame_id: impl Into<String>) -> Self {
+        self.frame_id = Some(frame_id.into());
+        self
+    }
+}
+
+/// Options for `act` and `act_with_action`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ActOptions {
+    pub model: Option<Model>,
+    /// Values for `%name%` placeholders in the instruction
+    pub variables: HashMap<String, VariableValue>,
+    /// Timeout in milliseconds
+    pub timeout: Option<u32>,
+    pub frame_id: Option<String>,
+}
+
+impl ActOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    pub fn variable(mut self, name: impl Into<String>, value: impl Into<VariableValue>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn variables(mut self, variables: HashMap<String, VariableValue>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    pub fn timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout = Some(timeout_ms);
+        self
+    }
+
+    pub fn frame_id(mut self, frame_id: impl Into<String>) -> Self {
+        self.frame_id = Some(frame_id.into());
+        self
+    }
+}
+
+/// Options for `extract`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ExtractOptions {
+    pub model: Option<Model>,
+    /// Timeout in milliseconds
+    pub timeout: Option<u32>,
+    /// Selector to narrow extraction scope
+    pub selector: Option<String>,
+    pub frame_id: Option<String>,
+}
+
+impl ExtractOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    pub fn timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout = Some(timeout_ms);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn frame_id(mut self, frame_id: impl Into<String>) -> Self {
+        self.frame_id = Some(frame_id.into());
+        self
+    }
+}
+
+/// Options for `observe`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ObserveOptions {
+    /// What to look for. Without an instruction, observe returns all interactive elements.
+    pub instruction: Option<String>,
+    pub model: Option<Model>,
+    /// Timeout in milliseconds
+    pub timeout: Option<u32>,
+    /// Selector to narrow observation scope
+    pub selector: Option<String>,
+    pub frame_id: Option<String>,
+}
+
+impl ObserveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.instruction = Some(instruction.into());
+        self
+    }
+
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    pub fn timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout = Some(timeout_ms);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn frame_id(mut self, frame_id: impl Into<String>) -> Self {
+        self.frame_id = Some(frame_id.into());
+        self
+    }
 }
 
 // =============================================================================
@@ -300,6 +616,11 @@ pub enum StagehandError {
     Transport(String),
     Api(String),
     MissingApiKey(String),
+    Cache(String),
+    Io(String),
+    Cdp(String),
+    Timeout(String),
+    Pdf(String),
 }
 
 impl fmt::Display for StagehandError {
@@ -308,18 +629,48 @@ impl fmt::Display for StagehandError {
             StagehandError::Transport(msg) => write!(f, "Transport error: {}", msg),
             StagehandError::Api(msg) => write!(f, "API error: {}", msg),
             StagehandError::MissingApiKey(key) => write!(f, "Missing API key: {}", key),
+            StagehandError::Cache(msg) => write!(f, "Cache error: {}", msg),
+            StagehandError::Io(msg) => write!(f, "IO error: {}", msg),
+            StagehandError::Cdp(msg) => write!(f, "CDP error: {}", msg),
+            StagehandError::Timeout(msg) => write!(f, "Timeout: {}", msg),
+            StagehandError::Pdf(msg) => write!(f, "PDF error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for StagehandError {}
 
+impl StagehandError {
+    /// The same error with `context` on a new line after its message
+    #[cfg(feature = "chromiumoxide")]
+    pub(crate) fn with_context(self, context: &str) -> Self {
+        let add = |msg: String| format!("{}\n{}", msg, context);
+        match self {
+            StagehandError::Transport(msg) => StagehandError::Transport(add(msg)),
+            StagehandError::Api(msg) => StagehandError::Api(add(msg)),
+            // The payload is the name of the missing key
+            StagehandError::MissingApiKey(key) => StagehandError::MissingApiKey(key),
+            StagehandError::Cache(msg) => StagehandError::Cache(add(msg)),
+            StagehandError::Io(msg) => StagehandError::Io(add(msg)),
+            StagehandError::Cdp(msg) => StagehandError::Cdp(add(msg)),
+            StagehandError::Timeout(msg) => StagehandError::Timeout(add(msg)),
+            StagehandError::Pdf(msg) => StagehandError::Pdf(add(msg)),
+        }
+    }
+}
+
 impl From<reqwest::Error> for StagehandError {
     fn from(err: reqwest::Error) -> Self {
         StagehandError::Transport(err.to_string())
     }
 }
 
+impl From<std::io::Error> for StagehandError {
+    fn from(err: std::io::Error) -> Self {
+        StagehandError::Io(err.to_string())
+    }
+}
+
 impl From<eventsource_client::Error> for StagehandError {
     fn from(err: eventsource_client::Error) -> Self {
         StagehandError::Transport(err.to_string())
@@ -334,10 +685,10 @@ impl From<eventsource_client::Error> for StagehandError {
 #[async_trait]
 pub trait Transport: Send + Sync {
     async fn start(&mut self, opts: V3Options) -> Result<Pin<Box<dyn Stream<Item = Result<StartResponse, StagehandError>> + Send>>, StagehandError>;
-    async fn navigate(&mut self, session_id: &str, url: String, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError>;
-    async fn act(&mut self, session_id: &str, instruction: String, model: Option<Model>, variables: HashMap<String, String>, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError>;
-    async fn extract(&mut self, session_id: &str, instruction: String, schema: serde_json::Value, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError>;
-    async fn observe(&mut self, session_id: &str, instruction: Option<String>, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError>;
+    async fn navigate(&mut self, session_id: &str, url: String, options: NavigateOptions) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError>;
+    async fn act(&mut self, session_id: &str, input: ActInput, options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError>;
+    async fn extract(&mut self, session_id: &str, instruction: String, schema: serde_json::Value, options: ExtractOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError>;
+    async fn observe(&mut self, session_id: &str, options: ObserveOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError>;
     async fn execute(&mut self, session_id: &str, agent_config: AgentConfig, execute_options: AgentExecuteOptions, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ExecuteResponse, StagehandError>> + Send>>, StagehandError>;
     async fn end(&mut self, session_id: &str) -> Result<(), StagehandError>;
 }
@@ -455,6 +806,34 @@ impl RestTransport {
         })))
     }
 
+    fn act_result_events(result: &serde_json::Value) -> Vec<Result<ActResponse, StagehandError>> {
+        let mut events = Vec::new();
+        if let Ok(actions) = serde_json::from_value::<Vec<ObservedAction>>(result["actions"].clone()) {
+            events.push(Ok(ActResponse { event: Some(ActResponseEvent::Actions(actions)) }));
+        }
+        let success = result["success"].as_bool().unwrap_or(true);
+        events.push(Ok(ActResponse { event: Some(ActResponseEvent::Success(success)) }));
+        events
+    }
+
+    fn redact_act_event(event: Result<ActResponse, StagehandError>, secrets: &[(String, String)]) -> Result<ActResponse, StagehandError> {
+        match event {
+            Ok(ActResponse { event: Some(ActResponseEvent::Log(log)) }) => Ok(ActResponse {
+                event: Some(ActResponseEvent::Log(LogLine { message: variables::redact(&log.message, secrets), ..log })),
+            }),
+            Ok(ActResponse { event: Some(ActResponseEvent::Actions(actions)) }) => Ok(ActResponse {
+                event: Some(ActResponseEvent::Actions(actions.into_iter().map(|action| ObservedAction {
+                    description: variables::redact(&action.description, secrets),
+                    arguments: action.arguments.iter().map(|arg| variables::redact(arg, secrets)).collect(),
+                    ..action
+                }).collect())),
+            }),
+            Err(StagehandError::Api(msg)) => Err(StagehandError::Api(variables::redact(&msg, secrets))),
+            Err(StagehandError::Transport(msg)) => Err(StagehandError::Transport(variables::redact(&msg, secrets))),
+            other => other,
+        }
+    }
+
     fn parse_log_event(json_value: &serde_json::Value) -> Option<LogLine> {
         let data = &json_value["data"];
         Some(LogLine {
@@ -482,7 +861,7 @@ impl Transport for RestTransport {
             #[serde(skip_serializing_if = "Option::is_none")]
             wait_for_captcha_solves: Option<bool>,
             #[serde(skip_serializing_if = "Option::is_none")]
-            browserbase_session_create_params: Option<&'a serde_json::Value>,
+            browserbase_session_create_params: Option<&'a BrowserbaseSessionCreateParams>,
             #[serde(rename = "browserbaseSessionID")]
             #[serde(skip_serializing_if = "Option::is_none")]
             browserbase_session_id: Option<&'a String>,
@@ -551,29 +930,38 @@ impl Transport for RestTransport {
         Ok(Box::pin(futures::stream::once(async move { Ok(result) })))
     }
 
-    async fn navigate(&mut self, session_id: &str, url: String, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError> {
+    async fn navigate(&mut self, session_id: &str, url: String, options: NavigateOptions) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct NavigatePayload {
             url: String,
             #[serde(skip_serializing_if = "Option::is_none")]
-            options: Option<NavigateOptions>,
+            options: Option<NavigatePayloadOptions>,
             // V3 schema requires frameId, use empty string for active page
             frame_id: String,
         }
 
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
-        struct NavigateOptions {
+        struct NavigatePayloadOptions {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            referer: Option<String>,
             #[serde(skip_serializing_if = "Option::is_none")]
             timeout: Option<u32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            wait_until: Option<LoadState>,
         }
 
-        let options = timeout.map(|t| NavigateOptions { timeout: Some(t) });
+        let NavigateOptions { referer, timeout, wait_until, frame_id } = options;
+        let payload_options = if referer.is_some() || timeout.is_some() || wait_until.is_some() {
+            Some(NavigatePayloadOptions { referer, timeout, wait_until })
+        } else {
+            None
+        };
 
         let payload = NavigatePayload {
             url,
-            options,
+            options: payload_options,
             // Use provided frame_id or empty string (backend uses active page for empty/falsy)
             frame_id: frame_id.unwrap_or_default(),
         };
@@ -616,35 +1004,40 @@ impl Transport for RestTransport {
         })))
     }
 
-    async fn act(&mut self, session_id: &str, instruction: String, model: Option<Model>, variables: HashMap<String, String>, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+    async fn act(&mut self, session_id: &str, input: ActInput, options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct ActPayload {
-            input: String,
+            input: ActInput,
             #[serde(skip_serializing_if = "Option::is_none")]
-            options: Option<ActOptions>,
+            options: Option<ActPayloadOptions>,
             #[serde(skip_serializing_if = "Option::is_none")]
             frame_id: Option<String>,
         }
 
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
-        struct ActOptions {
+        struct ActPayloadOptions {
             #[serde(skip_serializing_if = "Option::is_none")]
             model: Option<ModelObj>,
             #[serde(skip_serializing_if = "Option::is_none")]
-            variables: Option<HashMap<String, String>>,
+            variables: Option<HashMap<String, VariableValue>>,
             #[serde(skip_serializing_if = "Option::is_none")]
             timeout: Option<u32>,
         }
 
-        let model_obj = model.map(|m| match m {
-            Model::String(s) => ModelObj { model_name: s, api_key: None, base_url: None },
-            Model::Config { model_name, api_key, base_url } => ModelObj { model_name, api_key, base_url },
-        });
+        let ActOptions { model, variables, timeout, frame_id } = options;
+        let model_obj = model.map(ModelObj::from);
 
-        let options = if model_obj.is_some() || !variables.is_empty() || timeout.is_some() {
-            Some(ActOptions {
+        // Secret values are redacted back to their placeholder in the events and errors of this stream
+        let secrets: Vec<(String, String)> = variables
+            .iter()
+            .filter(|(_, value)| value.is_secret())
+            .map(|(name, value)| (name.clone(), value.expose().to_string()))
+            .collect();
+
+        let payload_options = if model_obj.is_some() || !variables.is_empty() || timeout.is_some() {
+            Some(ActPayloadOptions {
                 model: model_obj,
                 variables: if variables.is_empty() { None } else { Some(variables) },
                 timeout,
@@ -654,52 +1047,58 @@ impl Transport for RestTransport {
         };
 
         let payload = ActPayload {
-            input: instruction,
-            options,
+            input,
+            options: payload_options,
             frame_id,
         };
 
         let body = serde_json::to_value(payload).map_err(|e| StagehandError::Api(e.to_string()))?;
         let json_stream = self.execute_stream(session_id, &format!("/sessions/{}/act", session_id), body).await?;
 
-        Ok(Box::pin(json_stream.map(|item| {
-            item.and_then(|json_value| {
-                if let Some(event_type) = json_value["type"].as_str() {
-                    match event_type {
-                        "system" => {
-                            if let Some(status) = json_value["data"]["status"].as_str() {
-                                match status {
-                                    "finished" => {
-                                        let success = json_value["data"]["result"]["success"].as_bool().unwrap_or(true);
-                                        Ok(ActResponse { event: Some(ActResponseEvent::Success(success)) })
-                                    },
-                                    "error" => {
-                                        Err(StagehandError::Api(json_value["data"]["error"].as_str().unwrap_or("Unknown error").to_string()))
-                                    },
-                                    _ => Ok(ActResponse { event: None })
+        // A finished act produces two events (the performed actions, then success), so flatten
+        Ok(Box::pin(json_stream.flat_map(move |item| {
+            let events = match item {
+                Ok(json_value) => {
+                    if let Some(event_type) = json_value["type"].as_str() {
+                        match event_type {
+                            "system" => {
+                                if let Some(status) = json_value["data"]["status"].as_str() {
+                                    match status {
+                                        "finished" => RestTransport::act_result_events(&json_value["data"]["result"]),
+                                        "error" => {
+                                            vec![Err(StagehandError::Api(json_value["data"]["error"].as_str().unwrap_or("Unknown error").to_string()))]
+                                        },
+                                        _ => vec![Ok(ActResponse { event: None })]
+                                    }
+                                } else {
+                                    vec![Ok(ActResponse { event: None })]
                                 }
-                            } else {
-                                Ok(ActResponse { event: None })
-                            }
-                        },
-                        "log" => {
-                            if let Some(log) = RestTransport::parse_log_event(&json_value) {
-                                Ok(ActResponse { event: Some(ActResponseEvent::Log(log)) })
-                            } else {
-                                Ok(ActResponse { event: None })
-                            }
-                        },
-                        _ => Ok(ActResponse { event: None })
+                            },
+                            "log" => {
+                                if let Some(log) = RestTransport::parse_log_event(&json_value) {
+                                    vec![Ok(ActResponse { event: Some(ActResponseEvent::Log(log)) })]
+                                } else {
+                                    vec![Ok(ActResponse { event: None })]
+                                }
+                            },
+                            _ => vec![Ok(ActResponse { event: None })]
+                        }
+                    } else {
+                        RestTransport::act_result_events(&json_value)
                     }
-                } else {
-                    let success = json_value["success"].as_bool().unwrap_or(true);
-                    Ok(ActResponse { event: Some(ActResponseEvent::Success(success)) })
-                }
-            })
+                },
+                Err(e) => vec![Err(e)],
+            };
+            let events: Vec<_> = if secrets.is_empty() {
+                events
+            } else {
+                events.into_iter().map(|event| RestTransport::redact_act_event(event, &secrets)).collect()
+            };
+            futures::stream::iter(events)
         })))
     }
 
-    async fn extract(&mut self, session_id: &str, instruction: String, schema: serde_json::Value, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError> {
+    async fn extract(&mut self, session_id: &str, instruction: String, schema: serde_json::Value, options: ExtractOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct ExtractPayload {
@@ -708,14 +1107,14 @@ impl Transport for RestTransport {
             #[serde(skip_serializing_if = "Option::is_none")]
             schema: Option<serde_json::Value>,
             #[serde(skip_serializing_if = "Option::is_none")]
-            options: Option<ExtractOptions>,
+            options: Option<ExtractPayloadOptions>,
             #[serde(skip_serializing_if = "Option::is_none")]
             frame_id: Option<String>,
         }
 
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
-        struct ExtractOptions {
+        struct ExtractPayloadOptions {
             #[serde(skip_serializing_if = "Option::is_none")]
             model: Option<ModelObj>,
             #[serde(skip_serializing_if = "Option::is_none")]
@@ -724,13 +1123,11 @@ impl Transport for RestTransport {
             selector: Option<String>,
         }
 
-        let model_obj = model.map(|m| match m {
-            Model::String(s) => ModelObj { model_name: s, api_key: None, base_url: None },
-            Model::Config { model_name, api_key, base_url } => ModelObj { model_name, api_key, base_url },
-        });
+        let ExtractOptions { model, timeout, selector, frame_id } = options;
+        let model_obj = model.map(ModelObj::from);
 
-        let options = if model_obj.is_some() || timeout.is_some() || selector.is_some() {
-            Some(ExtractOptions {
+        let payload_options = if model_obj.is_some() || timeout.is_some() || selector.is_some() {
+            Some(ExtractPayloadOptions {
                 model: model_obj,
                 timeout,
                 selector,
@@ -742,7 +1139,7 @@ impl Transport for RestTransport {
         let payload = ExtractPayload {
             instruction: if instruction.is_empty() { None } else { Some(instruction) },
             schema: if schema.is_null() { None } else { Some(schema) },
-            options,
+            options: payload_options,
             frame_id,
         };
 
@@ -785,21 +1182,21 @@ impl Transport for RestTransport {
         })))
     }
 
-    async fn observe(&mut self, session_id: &str, instruction: Option<String>, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError> {
+    async fn observe(&mut self, session_id: &str, options: ObserveOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct ObservePayload {
             #[serde(skip_serializing_if = "Option::is_none")]
             instruction: Option<String>,
             #[serde(skip_serializing_if = "Option::is_none")]
-            options: Option<ObserveOptions>,
+            options: Option<ObservePayloadOptions>,
             #[serde(skip_serializing_if = "Option::is_none")]
             frame_id: Option<String>,
         }
 
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
-        struct ObserveOptions {
+        struct ObservePayloadOptions {
             #[serde(skip_serializing_if = "Option::is_none")]
             model: Option<ModelObj>,
             #[serde(skip_serializing_if = "Option::is_none")]
@@ -808,13 +1205,11 @@ impl Transport for RestTransport {
             selector: Option<String>,
         }
 
-        let model_obj = model.map(|m| match m {
-            Model::String(s) => ModelObj { model_name: s, api_key: None, base_url: None },
-            Model::Config { model_name, api_key, base_url } => ModelObj { model_name, api_key, base_url },
-        });
+        let ObserveOptions { instruction, model, timeout, selector, frame_id } = options;
+        let model_obj = model.map(ModelObj::from);
 
-        let options = if model_obj.is_some() || timeout.is_some() || selector.is_some() {
-            Some(ObserveOptions {
+        let payload_options = if model_obj.is_some() || timeout.is_some() || selector.is_some() {
+            Some(ObservePayloadOptions {
                 model: model_obj,
                 timeout,
                 selector,
@@ -825,7 +1220,7 @@ impl Transport for RestTransport {
 
         let payload = ObservePayload {
             instruction,
-            options,
+            options: payload_options,
             frame_id,
         };
 
@@ -940,9 +1335,268 @@ impl Transport for RestTransport {
 // The Stagehand Client
 // =============================================================================
 
+/// Transport shared between `Stagehand` and its page handles
+pub(crate) type SharedTransport = Arc<futures::lock::Mutex<Box<dyn Transport + Send + Sync>>>;
+
+/// Streamed events of a server operation
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, StagehandError>> + Send>>;
+
+/// Log line for a cache write that failed. The operation it belongs to still succeeds, as the
+/// cache only saves work on later calls.
+fn cache_failure_log(e: &StagehandError) -> LogLine {
+    LogLine { message: format!("Result not cached: {}", e), status: None }
+}
+
+/// Adds context to an error from an AI method
+pub(crate) type ErrorAnnotator = Arc<dyn Fn(StagehandError) -> StagehandError + Send + Sync>;
+
+/// Called as an AI method starts, for the annotator of that operation
+pub(crate) type ErrorAnnotators = Arc<dyn Fn() -> ErrorAnnotator + Send + Sync>;
+
+/// State the AI methods of [`Stagehand`] and its [`Page`]s share, so both go through the same
+/// caches and add the same page error context
+#[derive(Clone)]
+pub(crate) struct AiSession {
+    transport: SharedTransport,
+    session_id: String,
+    action_cache: Option<Arc<dyn ActionCache>>,
+    extract_cache: Option<Arc<ExtractCache>>,
+    /// Set while page events are captured
+    annotate_errors: Option<ErrorAnnotators>,
+}
+
+impl AiSession {
+    pub(crate) fn has_action_cache(&self) -> bool {
+        self.action_cache.is_some()
+    }
+
+    pub(crate) fn has_extract_cache(&self) -> bool {
+        self.extract_cache.is_some()
+    }
+
+    /// `act` through the action cache, keyed by `url` and `frame_url`. Uncached when `url` is
+    /// unknown.
+    pub(crate) async fn act(&self, url: Option<String>, frame_url: Option<String>, instruction: String, options: ActOptions) -> Result<ResponseStream<ActResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.act_cached(url, frame_url, instruction, options).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    async fn act_cached(&self, url: Option<String>, frame_url: Option<String>, instruction: String, options: ActOptions) -> Result<ResponseStream<ActResponse>, StagehandError> {
+        let (Some(cache), Some(url)) = (self.action_cache.clone(), url) else {
+            return self.transport.lock().await.act(&self.session_id, ActInput::Instruction(instruction), options).await;
+        };
+
+        let key = ActionCacheKey::new(&url, &instruction, &options.variables, frame_url.as_deref());
+        let mut cache_failures = Vec::new();
+        if let Some(actions) = cache.get(&key)?.filter(|actions| !actions.is_empty()) {
+            match self.replay_actions(actions, &options).await {
+                Some(events) => return Ok(Box::pin(futures::stream::iter(events))),
+                None => {
+                    let (cache, key) = (cache.clone(), key.clone());
+                    if let Err(e) = spawn_blocking(move || cache.remove(&key)).await {
+                        cache_failures.push(Ok(ActResponse { event: Some(ActResponseEvent::Log(cache_failure_log(&e))) }));
+                    }
+                }
+            }
+        }
+
+        let stream = self.transport.lock().await.act(&self.session_id, ActInput::Instruction(instruction), options).await?;
+        let mut performed = Vec::new();
+        let stream = stream.then(move |item| {
+            let write = match &item {
+                Ok(ActResponse { event: Some(ActResponseEvent::Actions(actions)) }) => {
+                    performed.extend(actions.iter().cloned());
+                    None
+                },
+                Ok(ActResponse { event: Some(ActResponseEvent::Success(true)) }) if !performed.is_empty() => {
+                    Some((cache.clone(), key.clone(), std::mem::take(&mut performed)))
+                },
+                _ => None,
+            };
+            async move {
+                let Some((cache, key, actions)) = write else {
+                    return vec![item];
+                };
+                // The server already performed the actions, so a failed write is reported in the
+                // stream rather than turning the act into an error that invites a retry
+                match spawn_blocking(move || cache.put(&key, actions)).await {
+                    Ok(()) => vec![item],
+                    Err(e) => vec![Ok(ActResponse { event: Some(ActResponseEvent::Log(cache_failure_log(&e))) }), item],
+                }
+            }
+        });
+        Ok(Box::pin(futures::stream::iter(cache_failures).chain(stream.flat_map(futures::stream::iter))))
+    }
+
+    /// Replay cached actions in order, each to completion. Returns the events of a single act
+    /// (logs, the actions, then success), or `None` if any action fails so the caller can fall back
+    /// to inference.
+    async fn replay_actions(&self, actions: Vec<ObservedAction>, options: &ActOptions) -> Option<Vec<Result<ActResponse, StagehandError>>> {
+        let mut events = Vec::new();
+        let mut performed = Vec::new();
+        for action in actions {
+            let replay = self.transport.lock().await.act(&self.session_id, ActInput::Action(action), options.clone()).await.ok()?;
+            let mut succeeded = false;
+            for event in replay.collect::<Vec<_>>().await {
+                match event.ok()?.event {
+                    Some(ActResponseEvent::Actions(actions)) => performed.extend(actions),
+                    Some(ActResponseEvent::Success(success)) => succeeded = success,
+                    log => events.push(Ok(ActResponse { event: log })),
+                }
+            }
+            if !succeeded {
+                return None;
+            }
+        }
+        events.push(Ok(ActResponse { event: Some(ActResponseEvent::Actions(performed)) }));
+        events.push(Ok(ActResponse { event: Some(ActResponseEvent::Success(true)) }));
+        Some(events)
+    }
+
+    /// Upload `path` to the session and set it on the file input `target` resolves to
+    pub(crate) async fn upload_file(&self, browserbase: &BrowserbaseClient, target: UploadTarget, path: &std::path::Path) -> Result<ResponseStream<ActResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.set_input_file(browserbase, target, path).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    async fn set_input_file(&self, browserbase: &BrowserbaseClient, target: UploadTarget, path: &std::path::Path) -> Result<ResponseStream<ActResponse>, StagehandError> {
+        let (selector, description) = match target {
+            UploadTarget::Selector(selector) => (selector, "File input".to_string()),
+            UploadTarget::Instruction(instruction) => {
+                let mut stream = self.transport.lock().await.observe(&self.session_id, ObserveOptions::new().instruction(instruction.clone())).await?;
+                let mut elements = Vec::new();
+                while let Some(item) = stream.next().await {
+                    if let Some(ObserveResponseEvent::ElementsJson(json)) = item?.event {
+                        elements = serde_json::from_str::<Vec<ObservedAction>>(&json)
+                            .map_err(|e| StagehandError::Api(format!("Failed to parse observed elements: {}", e)))?;
+                    }
+                }
+                let element = elements.into_iter().next()
+                    .ok_or_else(|| StagehandError::Api(format!("No file input found for: {}", instruction)))?;
+                (element.selector, element.description)
+            }
+        };
+
+        let remote_path = browserbase.upload_file(&self.session_id, path).await?;
+        let action = ObservedAction {
+            selector,
+            description,
+            backend_node_id: None,
+            method: Some("setInputFiles".to_string()),
+            arguments: vec![remote_path],
+        };
+        self.transport.lock().await.act(&self.session_id, ActInput::Action(action), ActOptions::default()).await
+    }
+
+    pub(crate) async fn act_with_action(&self, action: ObservedAction, options: ActOptions) -> Result<ResponseStream<ActResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.transport.lock().await.act(&self.session_id, ActInput::Action(action), options).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    /// `extract` through the extract cache, keyed by `url` and `frame_url`. Uncached when `url`
+    /// is unknown.
+    pub(crate) async fn extract(&self, url: Option<String>, frame_url: Option<String>, instruction: String, schema: serde_json::Value, options: ExtractOptions) -> Result<ResponseStream<ExtractResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.extract_cached(url, frame_url, instruction, schema, options).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    async fn extract_cached(&self, url: Option<String>, frame_url: Option<String>, instruction: String, schema: serde_json::Value, options: ExtractOptions) -> Result<ResponseStream<ExtractResponse>, StagehandError> {
+        let (Some(cache), Some(url)) = (self.extract_cache.clone(), url) else {
+            return self.transport.lock().await.extract(&self.session_id, instruction, schema, options).await;
+        };
+
+        let dom_hash = if cache.checks_page_content() {
+            Some(self.page_content_hash(options.timeout, options.frame_id.clone()).await?)
+        } else {
+            None
+        };
+        let key = ExtractCacheKey::new(&url, &instruction, &schema, options.selector.as_deref(), dom_hash, frame_url.as_deref());
+        if let Some(data_json) = cache.get(&key) {
+            return Ok(Box::pin(futures::stream::once(async move {
+                Ok(ExtractResponse { event: Some(ExtractResponseEvent::DataJson(data_json)) })
+            })));
+        }
+
+        let stream = self.transport.lock().await.extract(&self.session_id, instruction, schema, options).await?;
+        Ok(Box::pin(stream.then(move |item| {
+            let write = match &item {
+                Ok(ExtractResponse { event: Some(ExtractResponseEvent::DataJson(json)) }) => Some((cache.clone(), key.clone(), json.clone())),
+                _ => None,
+            };
+            async move {
+                let Some((cache, key, json)) = write else {
+                    return vec![item];
+                };
+                // The extraction succeeded, so a failed write is reported rather than returned
+                match spawn_blocking(move || cache.put(key, json)).await {
+                    Ok(()) => vec![item],
+                    Err(e) => vec![Ok(ExtractResponse { event: Some(ExtractResponseEvent::Log(cache_failure_log(&e))) }), item],
+                }
+            }
+        }).flat_map(futures::stream::iter)))
+    }
+
+    /// Hash of the page content as reported by the server. An extract without instruction or
+    /// schema returns the page text without LLM inference.
+    async fn page_content_hash(&self, timeout: Option<u32>, frame_id: Option<String>) -> Result<String, StagehandError> {
+        let options = ExtractOptions { timeout, frame_id, ..Default::default() };
+        let mut stream = self.transport.lock().await.extract(&self.session_id, String::new(), serde_json::Value::Null, options).await?;
+        while let Some(item) = stream.next().await {
+            if let Some(ExtractResponseEvent::DataJson(json)) = item?.event {
+                return Ok(cache::stable_hash(json.as_bytes()));
+            }
+        }
+        Err(StagehandError::Api("Page content extract returned no data".to_string()))
+    }
+
+    pub(crate) async fn observe(&self, options: ObserveOptions) -> Result<ResponseStream<ObserveResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.transport.lock().await.observe(&self.session_id, options).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    pub(crate) async fn navigate(&self, url: String, options: NavigateOptions) -> Result<ResponseStream<NavigateResponse>, StagehandError> {
+        let annotate = self.error_annotator();
+        let result = self.transport.lock().await.navigate(&self.session_id, url, options).await;
+        Self::with_page_errors(annotate, result)
+    }
+
+    /// Annotator for an AI method starting now, while page events are captured
+    pub(crate) fn error_annotator(&self) -> Option<ErrorAnnotator> {
+        self.annotate_errors.as_ref().map(|annotators| annotators())
+    }
+
+    /// Adds the page errors reported since `annotate` was made to errors from an AI method
+    pub(crate) fn with_page_errors<T: Send + 'static>(annotate: Option<ErrorAnnotator>, result: Result<ResponseStream<T>, StagehandError>) -> Result<ResponseStream<T>, StagehandError> {
+        let Some(annotate) = annotate else {
+            return result;
+        };
+        match result {
+            Ok(stream) => Ok(Box::pin(stream.map(move |item| item.map_err(&*annotate)))),
+            Err(err) => Err(annotate(err)),
+        }
+    }
+}
+
 pub struct Stagehand {
-    transport: Box<dyn Transport + Send + Sync>,
+    transport: SharedTransport,
     session_id: Option<String>,
+    /// URL of the last `navigate` call, cleared once an AI method may have navigated away. Scopes
+    /// cache entries when the page URL can't be read over CDP.
+    page_url: Option<String>,
+    action_cache: Option<Arc<dyn ActionCache>>,
+    extract_cache: Option<Arc<ExtractCache>>,
+    browserbase: BrowserbaseClient,
+    #[cfg(feature = "chromiumoxide")]
+    cdp: Option<CdpBrowser>,
+    #[cfg(feature = "chromiumoxide")]
+    har_capture: Option<HarCapture>,
+    #[cfg(feature = "chromiumoxide")]
+    page_events: Option<PageEventCapture>,
 }
 
 impl Stagehand {
@@ -950,19 +1604,78 @@ impl Stagehand {
         let transport: Box<dyn Transport + Send + Sync> = match transport_choice {
             TransportChoice::Rest(base_url) => Box::new(RestTransport::new(base_url)?),
         };
-        Ok(Self { transport, session_id: None })
+        let browserbase = BrowserbaseClient::from_env()?;
+        Ok(Self {
+            transport: Arc::new(futures::lock::Mutex::new(transport)),
+            session_id: None,
+            page_url: None,
+            action_cache: None,
+            extract_cache: None,
+            browserbase,
+            #[cfg(feature = "chromiumoxide")]
+            cdp: None,
+            #[cfg(feature = "chromiumoxide")]
+            har_capture: None,
+            #[cfg(feature = "chromiumoxide")]
+            page_events: None,
+        })
+    }
+
+    /// Browserbase platform API client, using the same credentials as the transport
+    pub fn browserbase(&self) -> &BrowserbaseClient {
+        &self.browserbase
+    }
+
+    /// Cache actions performed by `act`.
+    ///
+    /// With a cache set, `act` first replays the cached action for the current page, instruction
+    /// and variables without LLM inference. If there is no entry or the replay fails, it falls back
+    /// to a normal `act` and stores the action the server performed.
+    ///
+    /// Entries are scoped to the active page's URL (without query or fragment) and the frame.
+    /// With the `chromiumoxide` feature the URL is read from the browser before each `act`;
+    /// otherwise it is the URL of the last `navigate`, so only the first `act` after a `navigate` is
+    /// cached, as any `act` may navigate.
+    pub fn set_action_cache(&mut self, cache: Arc<dyn ActionCache>) {
+        self.action_cache = Some(cache);
+    }
+
+    /// Cache results of `extract`.
+    ///
+    /// With a cache set, `extract` returns the cached `DataJson` for the same URL, frame,
+    /// instruction, schema, selector and page content while the entry is within its TTL. The URL is
+    /// resolved as for [`Stagehand::set_action_cache`], and `extract` is uncached when it is unknown. Keep the `Arc` to read
+    /// hit/miss counts with [`ExtractCache::stats`].
+    pub fn set_extract_cache(&mut self, cache: Arc<ExtractCache>) {
+        self.extract_cache = Some(cache);
     }
 
     pub async fn start(&mut self, opts: V3Options) -> Result<(), StagehandError> {
-        let mut stream = self.transport.start(opts).await?;
+        #[cfg(not(feature = "chromiumoxide"))]
+        if opts.storage_state.is_some() {
+            return Err(StagehandError::Api("V3Options::storage_state needs the chromiumoxide feature".to_string()));
+        }
+        #[cfg(feature = "chromiumoxide")]
+        let storage_state = opts.storage_state.clone();
+        let mut stream = self.transport.lock().await.start(opts).await?;
         while let Some(item) = stream.next().await {
             match item {
                 Ok(response) => {
-                    if let Some(StartResponseEvent::Result(res)) = response.event {
-                        if !res.session_id.is_empty() {
-                            self.session_id = Some(res.session_id);
-                            return Ok(());
+                    if let Some(StartResponseEvent::Result(res)) = response.event
+                        && !res.session_id.is_empty()
+                    {
+                        self.session_id = Some(res.session_id);
+                        // Applying the state needs the session's browser. If it fails, end the
+                        // session rather than leave a running one behind an error
+                        #[cfg(feature = "chromiumoxide")]
+                        if let Some(state) = storage_state
+                            && let Err(e) = self.set_storage_state(&state).await
+                        {
+                            let _ = self.end().await;
+                            self.session_id = None;
+                            return Err(e);
                         }
+                        return Ok(());
                     }
                 },
                 Err(e) => return Err(e),
@@ -971,14 +1684,52 @@ impl Stagehand {
         Err(StagehandError::Api("Start did not return a session ID.".to_string()))
     }
 
-    pub async fn navigate(&mut self, url: impl Into<String>, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError> {
-        let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?.clone();
-        self.transport.navigate(&session_id, url.into(), timeout, frame_id).await
+    pub async fn navigate(&mut self, url: impl Into<String>, options: NavigateOptions) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError> {
+        let ai = self.ai_session()?;
+        let url = url.into();
+        if options.frame_id.is_none() {
+            self.page_url = Some(url.clone());
+        }
+        ai.navigate(url, options).await
     }
 
-    pub async fn act(&mut self, instruction: impl Into<String>, model: Option<Model>, variables: HashMap<String, String>, timeout: Option<u32>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
-        let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?.clone();
-        self.transport.act(&session_id, instruction.into(), model, variables, timeout, frame_id).await
+    pub async fn act(&mut self, instruction: impl Into<String>, options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+        let ai = self.ai_session()?;
+        let (url, frame_url) = if ai.has_action_cache() { self.cache_location(options.frame_id.as_deref()).await } else { (None, None) };
+        // The action may navigate, after which the URL of the last `navigate` is stale
+        self.page_url = None;
+        ai.act(url, frame_url, instruction.into(), options).await
+    }
+
+    /// Page URL and, for an iframe, frame URL to key cache entries by. With the `chromiumoxide`
+    /// feature both are read from the active page; without it the page URL is that of the last
+    /// `navigate` if no AI method has run since. The page URL is `None`, so nothing is cached,
+    /// when the frame's URL can't be found.
+    async fn cache_location(&mut self, frame_id: Option<&str>) -> (Option<String>, Option<String>) {
+        #[cfg(feature = "chromiumoxide")]
+        if let Ok(page) = self.active_page().await
+            && let (Some(url), frame_url) = page.cache_location(frame_id).await
+        {
+            return (Some(url), frame_url);
+        }
+        match frame_id {
+            Some(_) => (None, None),
+            None => (self.page_url.clone(), None),
+        }
+    }
+
+    /// Perform an action previously returned by `observe`.
+    ///
+    /// The action's selector, method and arguments are sent as-is, so the server executes it
+    /// deterministically without a second LLM call. This enables preview-then-execute flows:
+    /// ```ignore
+    /// let actions: Vec<ObservedAction> = serde_json::from_str(&elements_json)?;
+    /// let stream = stagehand.act_with_action(actions[0].clone(), ActOptions::default()).await?;
+    /// ```
+    pub async fn act_with_action(&mut self, action: ObservedAction, options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+        let ai = self.ai_session()?;
+        self.page_url = None;
+        ai.act_with_action(action, options).await
     }
 
     /// Extract data from the page using AI.
@@ -994,24 +1745,49 @@ impl Stagehand {
     ///   }
     /// }
     /// ```
-    pub async fn extract(&mut self, instruction: impl Into<String>, schema: serde_json::Value, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError> {
-        let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?.clone();
-        self.transport.extract(&session_id, instruction.into(), schema, model, timeout, selector, frame_id).await
+    pub async fn extract(&mut self, instruction: impl Into<String>, schema: serde_json::Value, options: ExtractOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError> {
+        let ai = self.ai_session()?;
+        let (url, frame_url) = if ai.has_extract_cache() { self.cache_location(options.frame_id.as_deref()).await } else { (None, None) };
+        ai.extract(url, frame_url, instruction.into(), schema, options).await
     }
 
-    pub async fn observe(&mut self, instruction: Option<String>, model: Option<Model>, timeout: Option<u32>, selector: Option<String>, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError> {
-        let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?.clone();
-        self.transport.observe(&session_id, instruction, model, timeout, selector, frame_id).await
+    pub async fn observe(&mut self, options: ObserveOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError> {
+        self.ai_session()?.observe(options).await
     }
 
     pub async fn execute(&mut self, agent_config: AgentConfig, execute_options: AgentExecuteOptions, frame_id: Option<String>) -> Result<Pin<Box<dyn Stream<Item = Result<ExecuteResponse, StagehandError>> + Send>>, StagehandError> {
+        let annotate = self.ai_session()?.error_annotator();
         let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?.clone();
-        self.transport.execute(&session_id, agent_config, execute_options, frame_id).await
+        self.page_url = None;
+        let result = self.transport.lock().await.execute(&session_id, agent_config, execute_options, frame_id).await;
+        AiSession::with_page_errors(annotate, result)
+    }
+
+    /// The transport, caches and page error context for the AI methods of the started session
+    fn ai_session(&self) -> Result<AiSession, StagehandError> {
+        let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
+        #[cfg(feature = "chromiumoxide")]
+        let annotate_errors = self.page_events.as_ref().map(|capture| Arc::new(capture.error_annotators()) as ErrorAnnotators);
+        #[cfg(not(feature = "chromiumoxide"))]
+        let annotate_errors = None;
+        Ok(AiSession {
+            transport: self.transport.clone(),
+            session_id: session_id.clone(),
+            action_cache: self.action_cache.clone(),
+            extract_cache: self.extract_cache.clone(),
+            annotate_errors,
+        })
     }
 
     pub async fn end(&mut self) -> Result<(), StagehandError> {
         let session_id = self.session_id.as_ref().ok_or_else(|| StagehandError::Api("Session not started".to_string()))?.clone();
-        self.transport.end(&session_id).await
+        #[cfg(feature = "chromiumoxide")]
+        {
+            self.har_capture = None;
+            self.page_events = None;
+            self.cdp = None;
+        }
+        self.transport.lock().await.end(&session_id).await
     }
 
     /// Returns the Browserbase session ID if initialized
@@ -1032,44 +1808,304 @@ impl Stagehand {
     pub async fn browserbase_cdp_url(&self) -> Result<String, StagehandError> {
         let session_id = self.session_id.as_ref()
             .ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
-        let api_key = std::env::var("BROWSERBASE_API_KEY")
-            .map_err(|_| StagehandError::MissingApiKey("BROWSERBASE_API_KEY".to_string()))?;
+        self.browserbase.session_connect_url(session_id).await
+    }
 
-        let browserbase_api_url = std::env::var("BROWSERBASE_API_URL")
-            .unwrap_or_else(|_| DEFAULT_BROWSERBASE_API_URL.to_string());
+    /// Attach a local file to an `<input type=file>`.
+    ///
+    /// The file is uploaded to the session through the Browserbase API, then set on the input
+    /// with a `setInputFiles` action. A natural language target is resolved to a selector with
+    /// `observe` first.
+    pub async fn upload_file(&mut self, target: UploadTarget, path: impl AsRef<std::path::Path>) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+        let ai = self.ai_session()?;
+        // Setting the file may trigger a navigation, after which the URL of the last `navigate` is stale
+        self.page_url = None;
+        ai.upload_file(&self.browserbase, target, path.as_ref()).await
+    }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .get(format!("{}/sessions/{}", browserbase_api_url, session_id))
-            .header("x-bb-api-key", &api_key)
-            .send()
-            .await
-            .map_err(|e| StagehandError::Api(format!("Failed to fetch session: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(StagehandError::Api(format!(
-                "Failed to fetch session: HTTP {}",
-                response.status()
-            )));
+    /// Live debugger URLs for watching or controlling this session in a browser
+    pub async fn live_urls(&self) -> Result<SessionLiveUrls, StagehandError> {
+        let session_id = self.session_id.as_ref()
+            .ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
+        self.browserbase.session_live_urls(session_id).await
+    }
+
+    /// Pause for a human to take over the session through its live view, e.g. to solve a
+    /// CAPTCHA `wait_for_captcha_solves` can't handle. Resumes when the human signals through
+    /// [`HandoffCompletion`] or the handoff's timeout elapses.
+    pub async fn hand_off_to_human(&self, handoff: &HumanHandoff, reason: impl Into<String>) -> Result<HandoffOutcome, StagehandError> {
+        let session_id = self.session_id.as_ref()
+            .ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
+        handoff.run(&self.browserbase, session_id, reason).await
+    }
+
+    /// rrweb recording of this session, for replay with [`write_cdn_replay_html`].
+    ///
+    /// Browserbase finishes processing recordings shortly after the session ends, so fetch
+    /// this after `end` for a complete replay.
+    pub async fn recording(&self) -> Result<Vec<RecordingEvent>, StagehandError> {
+        let session_id = self.session_id.as_ref()
+            .ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
+        self.browserbase.session_recording(session_id).await
+    }
+
+    /// chromiumoxide browser connected to this session over CDP.
+    ///
+    /// Connects on first use and reconnects if the connection has closed; the handler task is
+    /// managed by the returned [`CdpBrowser`].
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn cdp_browser(&mut self) -> Result<&CdpBrowser, StagehandError> {
+        if self.cdp.as_ref().is_none_or(CdpBrowser::is_closed) {
+            let cdp_url = self.browserbase_cdp_url().await?;
+            self.cdp = Some(CdpBrowser::connect(&cdp_url).await?);
         }
+        Ok(self.cdp.as_ref().expect("CDP browser connected above"))
+    }
 
-        let session_data: serde_json::Value = response.json().await
-            .map_err(|e| StagehandError::Api(format!("Failed to parse session response: {}", e)))?;
+    /// The session's active page (the most recently opened tab still open), for deterministic CDP
+    /// steps between AI calls
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn active_page(&mut self) -> Result<Page, StagehandError> {
+        let cdp_page = self.cdp_browser().await?.active_page().await?;
+        self.page_handle(cdp_page).await
+    }
 
-        let connect_url = session_data["connectUrl"]
-            .as_str()
-            .ok_or_else(|| StagehandError::Api("Session response missing connectUrl".to_string()))?;
-
-        // Fix URL format: Browserbase returns URLs like "wss://host?query" but AWS ELB
-        // requires a path before the query string. We normalize to "wss://host/?query"
-        if let Some((before_query, query)) = connect_url.split_once('?') {
-            // Check if there's no path after the host (no '/' after "://")
-            if let Some((_, after_scheme)) = before_query.split_once("://") {
-                if !after_scheme.contains('/') {
-                    return Ok(format!("{}/?{}", before_query, query));
+    /// All open pages (tabs) in the session
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn pages(&mut self) -> Result<Vec<Page>, StagehandError> {
+        let cdp_pages = self.cdp_browser().await?.browser.pages().await?;
+        let mut pages = Vec::with_capacity(cdp_pages.len());
+        for cdp_page in cdp_pages {
+            pages.push(self.page_handle(cdp_page).await?);
+        }
+        Ok(pages)
+    }
+
+    /// Open a new page (tab) at `url`
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn new_page(&mut self, url: impl Into<String>) -> Result<Page, StagehandError> {
+        let cdp_page = self.cdp_browser().await?.browser.new_page(url.into()).await?;
+        self.page_handle(cdp_page).await
+    }
+
+    #[cfg(feature = "chromiumoxide")]
+    async fn page_handle(&self, cdp_page: chromiumoxide::Page) -> Result<Page, StagehandError> {
+        Page::new(cdp_page, self.ai_session()?).await
+    }
+
+    /// Deterministic actions on elements of the active page matching `selector`, e.g.
+    /// `stagehand.locator("css=#email").await?.fill("me@example.com").await?`
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn locator(&mut self, selector: impl Into<String>) -> Result<Locator, StagehandError> {
+        Ok(self.active_page().await?.locator(selector))
+    }
+
+    /// Wait until the active page reaches `state` (see [`Page::wait_for_load_state`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn wait_for_load_state(&mut self, state: LoadState, timeout: std::time::Duration) -> Result<(), StagehandError> {
+        self.active_page().await?.wait_for_load_state(state, timeout).await
+    }
+
+    /// Wait until the active page's URL matches the glob `pattern`
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn wait_for_url(&mut self, pattern: &str, timeout: std::time::Duration) -> Result<(), StagehandError> {
+        self.active_page().await?.wait_for_url(pattern, timeout).await
+    }
+
+    /// Wait until an element of the active page matches `selector`
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn wait_for_selector(&mut self, selector: &str, timeout: std::time::Duration) -> Result<(), StagehandError> {
+        self.active_page().await?.wait_for_selector(selector, timeout).await
+    }
+
+    /// Wait until `predicate` holds for the active page (see [`Page::wait_for`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn wait_for<F, Fut>(&mut self, predicate: F, timeout: std::time::Duration) -> Result<(), StagehandError>
+    where
+        F: FnMut(Page) -> Fut,
+        Fut: std::future::Future<Output = Result<bool, StagehandError>>,
+    {
+        self.active_page().await?.wait_for(predicate, timeout).await
+    }
+
+    /// URL of the active page
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn current_url(&mut self) -> Result<Option<String>, StagehandError> {
+        self.active_page().await?.url().await
+    }
+
+    /// Title of the active page
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn title(&mut self) -> Result<Option<String>, StagehandError> {
+        self.active_page().await?.title().await
+    }
+
+    /// Run `expression` in the active page and deserialize its result (see [`Page::evaluate`]),
+    /// for checks where a line of JavaScript beats an LLM extract:
+    ///
+    /// ```ignore
+    /// let in_cart: u32 = stagehand.evaluate("() => window.cart.items.length", &[]).await?;
+    /// ```
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn evaluate<T: serde::de::DeserializeOwned>(&mut self, expression: &str, args: &[serde_json::Value]) -> Result<T, StagehandError> {
+        self.active_page().await?.evaluate(expression, args).await
+    }
+
+    /// [`evaluate`](Self::evaluate) in the frame `frame_id` of the active page (see [`frames`](Self::frames))
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn evaluate_in_frame<T: serde::de::DeserializeOwned>(&mut self, frame_id: &str, expression: &str, args: &[serde_json::Value]) -> Result<T, StagehandError> {
+        self.active_page().await?.evaluate_in_frame(frame_id, expression, args).await
+    }
+
+    /// HTML of the active page
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn content(&mut self) -> Result<String, StagehandError> {
+        self.active_page().await?.content().await
+    }
+
+    /// Accessibility tree of the active page
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn accessibility_tree(&mut self) -> Result<AccessibilityNode, StagehandError> {
+        self.active_page().await?.accessibility_tree().await
+    }
+
+    /// Screenshot of the active page (see [`Page::screenshot`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn screenshot(&mut self, options: ScreenshotOptions) -> Result<Vec<u8>, StagehandError> {
+        self.active_page().await?.screenshot(options).await
+    }
+
+    /// Print the active page to PDF (see [`Page::pdf`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn pdf(&mut self, options: PdfOptions) -> Result<Vec<u8>, StagehandError> {
+        self.active_page().await?.pdf(options).await
+    }
+
+    /// Text of the PDF open in the active page, e.g. an invoice or report the agent navigated to
+    /// (see [`Page::extract_from_pdf`])
+    #[cfg(all(feature = "chromiumoxide", feature = "pdf-text"))]
+    pub async fn extract_from_pdf(&mut self) -> Result<String, StagehandError> {
+        self.active_page().await?.extract_from_pdf().await
+    }
+
+    /// Intercept the active page's requests with `interceptor` until the returned handle is
+    /// detached or dropped (see [`Page::intercept_network`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn intercept_network(&mut self, interceptor: NetworkInterceptor) -> Result<InterceptorHandle, StagehandError> {
+        self.active_page().await?.intercept_network(interceptor).await
+    }
+
+    /// Start recording the active page's network traffic, replacing any capture in progress
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn start_har_capture(&mut self, options: HarCaptureOptions) -> Result<(), StagehandError> {
+        let capture = self.active_page().await?.start_har_capture(options).await?;
+        self.har_capture = Some(capture);
+        Ok(())
+    }
+
+    /// Stop recording and return the traffic since `start_har_capture` as a HAR 1.2 log
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn stop_har_capture(&mut self) -> Result<Har, StagehandError> {
+        let capture = self.har_capture.take()
+            .ok_or_else(|| StagehandError::Api("HAR capture not started".to_string()))?;
+        Ok(capture.stop().await)
+    }
+
+    /// Cookies of the session's browser and web storage of the origins its pages are on, e.g. to
+    /// save a login for [`V3Options::storage_state`]
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn storage_state(&mut self) -> Result<StorageState, StagehandError> {
+        StorageState::capture(&self.cdp_browser().await?.browser).await
+    }
+
+    /// Load cookies and web storage into the session's browser (see [`StorageState::apply`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn set_storage_state(&mut self, state: &StorageState) -> Result<(), StagehandError> {
+        let cdp = self.cdp_browser().await?;
+        let page = cdp.active_page().await?;
+        state.apply(&cdp.browser, &page).await
+    }
+
+    /// Console messages, uncaught exceptions and failed requests of the active page from now on.
+    ///
+    /// The first call starts capturing on the active page. From then on, errors from the AI
+    /// methods include the page's recent errors (see [`recent_page_errors`](Self::recent_page_errors)).
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn subscribe_page_events(&mut self) -> Result<PageEventStream, StagehandError> {
+        if self.page_events.is_none() {
+            let capture = self.active_page().await?.capture_events().await?;
+            self.page_events = Some(capture);
+        }
+        Ok(self.page_events.as_ref().map(PageEventCapture::subscribe).expect("capture started above"))
+    }
+
+    /// The most recent console errors, exceptions and failed requests, once
+    /// `subscribe_page_events` started capturing
+    #[cfg(feature = "chromiumoxide")]
+    pub fn recent_page_errors(&self) -> Vec<PageEvent> {
+        self.page_events.as_ref().map(PageEventCapture::recent_errors).unwrap_or_default()
+    }
+
+    /// Frame tree of the active page.
+    ///
+    /// Use a frame's `id` as `frame_id` in request options to target an iframe, such as an
+    /// embedded payment form.
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn frames(&mut self) -> Result<FrameInfo, StagehandError> {
+        self.active_page().await?.frames().await
+    }
+
+    /// First frame of the active page whose URL matches the glob `pattern` (see
+    /// [`FrameInfo::find_by_url`])
+    #[cfg(feature = "chromiumoxide")]
+    pub async fn frame_by_url(&mut self, pattern: &str) -> Result<Option<FrameInfo>, StagehandError> {
+        Ok(self.frames().await?.find_by_url(pattern).cloned())
+    }
+
+    /// Files the browser has downloaded during this session (e.g. after clicking "Export CSV")
+    pub async fn downloads(&self) -> Result<Vec<DownloadedFile>, StagehandError> {
+        let session_id = self.session_id.as_ref()
+            .ok_or_else(|| StagehandError::Api("Session not initialized".to_string()))?;
+        self.browserbase.session_downloads(session_id).await
+    }
+
+    /// Write the session's downloaded files into `dir`, creating it if needed, and return their paths
+    pub async fn downloads_to_dir(&self, dir: impl AsRef<std::path::Path>) -> Result<Vec<std::path::PathBuf>, StagehandError> {
+        let dir = dir.as_ref().to_path_buf();
+        let files = self.downloads().await?;
+        spawn_blocking(move || {
+            let mut paths = Vec::with_capacity(files.len());
+            for file in files {
+                // Names are relative and free of `..`, so this stays inside `dir`
+                let path = dir.join(&file.name);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
                 }
+                std::fs::write(&path, &file.bytes)?;
+                paths.push(path);
             }
-        }
-        Ok(connect_url.to_string())
+            Ok(paths)
+        })
+        .await
     }
 }
+
+// =============================================================================
+// Runtime Helpers
+// =============================================================================
+
+/// Run blocking work, like filesystem IO or parsing a large file, off the async executor
+#[cfg(feature = "tokio-runtime")]
+async fn spawn_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(work).await.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
+
+#[cfg(all(feature = "async-std-runtime", not(feature = "tokio-runtime")))]
+async fn spawn_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    async_std::task::spawn_blocking(work).await
+}
+
+#[cfg(not(any(feature = "tokio-runtime", feature = "async-std-runtime")))]
+async fn spawn_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    work()
+}
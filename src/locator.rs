@@ -0,0 +1,178 @@
+//! Deterministic element actions over CDP, for steps that don't need an LLM.
+//!
+//! Enabled with the `chromiumoxide` feature. A [`Locator`] re-resolves its selector for every
+//! action, waiting for the element to appear, so it works on pages that re-render.
+
+use chromiumoxide::cdp::browser_protocol::dom::{DiscardSearchResultsParams, PerformSearchParams};
+use chromiumoxide::element::Element;
+use std::time::Duration;
+
+use crate::StagehandError;
+use crate::page::Page;
+
+/// How long actions wait for the element to appear unless overridden with [`Locator::timeout`]
+pub const DEFAULT_LOCATOR_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A selector split into its engine and expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selector<'a> {
+    Css(&'a str),
+    XPath(&'a str),
+}
+
+impl<'a> Selector<'a> {
+    /// `xpath=` selects XPath and `css=` CSS; a selector without either prefix is CSS
+    pub fn parse(selector: &'a str) -> Self {
+        match selector.strip_prefix("xpath=") {
+            Some(xpath) => Selector::XPath(xpath),
+            None => Selector::Css(selector.strip_prefix("css=").unwrap_or(selector)),
+        }
+    }
+}
+
+/// Elements matching a selector on a page.
+///
+/// Selectors are CSS by default; prefix with `xpath=` for XPath or `css=` to be explicit.
+/// Actions apply to the first match.
+#[derive(Clone)]
+pub struct Locator {
+    page: Page,
+    selector: String,
+    timeout: Duration,
+}
+
+impl Locator {
+    pub(crate) fn new(page: Page, selector: String) -> Self {
+        Self { page, selector, timeout: DEFAULT_LOCATOR_TIMEOUT }
+    }
+
+    /// How long actions wait for the element to appear
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn selector(&self) -> &str {
+        &self.selector
+    }
+
+    pub fn page(&self) -> &Page {
+        &self.page
+    }
+
+    /// Wait until an element matches the selector
+    pub async fn wait_for(&self) -> Result<(), StagehandError> {
+        self.element().await.map(|_| ())
+    }
+
+    /// Number of elements currently matching, without waiting
+    pub async fn count(&self) -> Result<usize, StagehandError> {
+        let cdp = self.page.cdp();
+        match Selector::parse(&self.selector) {
+            Selector::Css(css) => Ok(cdp.find_elements(css).await?.len()),
+            // chromiumoxide's `find_xpaths` fails when nothing matches, so read the search's count
+            Selector::XPath(xpath) => {
+                cdp.get_document().await?;
+                let search = cdp.execute(PerformSearchParams::new(xpath)).await?.result;
+                cdp.execute(DiscardSearchResultsParams::new(search.search_id)).await?;
+                Ok(search.result_count as usize)
+            }
+        }
+    }
+
+    pub async fn click(&self) -> Result<(), StagehandError> {
+        self.element().await?.click().await?;
+        Ok(())
+    }
+
+    pub async fn hover(&self) -> Result<(), StagehandError> {
+        self.element().await?.scroll_into_view().await?.hover().await?;
+        Ok(())
+    }
+
+    /// Replace the value of an input or textarea by typing `text`
+    pub async fn fill(&self, text: &str) -> Result<(), StagehandError> {
+        let element = self.element().await?;
+        element.focus().await?;
+        element.call_js_fn(
+            "function() { this.value = ''; this.dispatchEvent(new Event('input', { bubbles: true })); }",
+            false,
+        ).await?;
+        element.type_str(text).await?;
+        Ok(())
+    }
+
+    /// Press a key on the element, e.g. `"Enter"` or `"Tab"`
+    pub async fn press(&self, key: &str) -> Result<(), StagehandError> {
+        let element = self.element().await?;
+        element.focus().await?;
+        element.press_key(key).await?;
+        Ok(())
+    }
+
+    /// Select the `<option>` of a `<select>` whose value or label is `value`
+    pub async fn select_option(&self, value: &str) -> Result<(), StagehandError> {
+        let value = serde_json::to_string(value).expect("strings serialize");
+        let function = format!(
+            "function() {{
+                const option = Array.from(this.options || []).find(o => o.value === {value} || o.label === {value});
+                if (!option) return false;
+                this.value = option.value;
+                this.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                this.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                return true;
+            }}"
+        );
+        let selected = self.element().await?.call_js_fn(function, false).await?;
+        if selected.result.value != Some(serde_json::Value::Bool(true)) {
+            return Err(StagehandError::Cdp(format!("No option {} in {}", value, self.selector)));
+        }
+        Ok(())
+    }
+
+    pub async fn scroll_into_view(&self) -> Result<(), StagehandError> {
+        self.element().await?.scroll_into_view().await?;
+        Ok(())
+    }
+
+    pub async fn inner_text(&self) -> Result<Option<String>, StagehandError> {
+        Ok(self.element().await?.inner_text().await?)
+    }
+
+    pub async fn attribute(&self, name: &str) -> Result<Option<String>, StagehandError> {
+        Ok(self.element().await?.attribute(name).await?)
+    }
+
+    /// First matching element, polling until the timeout while nothing matches.
+    ///
+    /// Other failures, like an invalid selector or a closed page, are returned right away.
+    async fn element(&self) -> Result<Element, StagehandError> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let mut unresolved = false;
+        loop {
+            let err = match self.page.find_element(&self.selector).await {
+                Ok(element) => return Ok(element),
+                Err(e) => e,
+            };
+            // chromiumoxide reports an empty match as an ordinary CDP error, so count to tell
+            // them apart. A match that fails to resolve may have just been replaced by a
+            // re-render, so it gets one more attempt.
+            if self.count().await? > 0 {
+                if unresolved {
+                    return Err(err);
+                }
+                unresolved = true;
+                continue;
+            }
+            unresolved = false;
+            if tokio::time::Instant::now() >= deadline {
+                return Err(StagehandError::Timeout(format!(
+                    "Timed out after {:?} waiting for {}",
+                    self.timeout, self.selector
+                )));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
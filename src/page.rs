@@ -0,0 +1,383 @@
+//! Handles for the pages (tabs) of a session.
+//!
+//! Enabled with the `chromiumoxide` feature. Each [`Page`] pairs a chromiumoxide page with the
+//! Stagehand session, and its AI methods target that page through its main frame id.
+
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, CaptureScreenshotParams, Viewport};
+use chromiumoxide::cdp::browser_protocol::page::FrameId;
+use chromiumoxide::cdp::js_protocol::runtime::{EvaluateParams, ExecutionContextId};
+use chromiumoxide::element::Element;
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+
+use crate::accessibility::{self, AccessibilityNode};
+use crate::har::{HarCapture, HarCaptureOptions};
+use crate::interceptor::{InterceptorHandle, NetworkInterceptor};
+use crate::locator::{Locator, Selector};
+use crate::page_events::PageEventCapture;
+use crate::pdf::PdfOptions;
+use crate::{
+    ActOptions, ActResponse, AiSession, ExtractOptions, ExtractResponse, FrameInfo, NavigateOptions, NavigateResponse,
+    ObserveOptions, ObserveResponse, ObservedAction, StagehandError,
+};
+
+/// Image format for screenshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl From<ImageFormat> for CaptureScreenshotFormat {
+    fn from(format: ImageFormat) -> Self {
+        match format {
+            ImageFormat::Png => CaptureScreenshotFormat::Png,
+            ImageFormat::Jpeg => CaptureScreenshotFormat::Jpeg,
+            ImageFormat::Webp => CaptureScreenshotFormat::Webp,
+        }
+    }
+}
+
+/// Region of the page in CSS pixels, relative to the top-left of the document
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Options for `screenshot`
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ScreenshotOptions {
+    /// Capture the whole scrollable page instead of the viewport
+    pub full_page: bool,
+    pub clip: Option<ClipRect>,
+    pub format: ImageFormat,
+    /// Compression quality (0-100), for JPEG and WebP only. `screenshot` rejects values above 100.
+    pub quality: Option<u8>,
+    /// Capture only the element matching this CSS selector (or `xpath=` selector)
+    pub selector: Option<String>,
+}
+
+impl ScreenshotOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn full_page(mut self, full_page: bool) -> Self {
+        self.full_page = full_page;
+        self
+    }
+
+    pub fn clip(mut self, clip: ClipRect) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+}
+
+/// CDP parameters for a screenshot of the viewport or `clip`. The element of `selector` and
+/// `full_page` are resolved by [`Page::screenshot`]. Fails if `quality` is above 100.
+impl TryFrom<&ScreenshotOptions> for CaptureScreenshotParams {
+    type Error = StagehandError;
+
+    fn try_from(options: &ScreenshotOptions) -> Result<Self, Self::Error> {
+        if let Some(quality) = options.quality.filter(|quality| *quality > 100) {
+            return Err(StagehandError::Cdp(format!("Screenshot quality must be between 0 and 100, got {}", quality)));
+        }
+        let clip = options.clip.map(|clip| Viewport { x: clip.x, y: clip.y, width: clip.width, height: clip.height, scale: 1.0 });
+        Ok(CaptureScreenshotParams {
+            format: Some(options.format.into()),
+            quality: options.quality.filter(|_| options.format != ImageFormat::Png).map(i64::from),
+            capture_beyond_viewport: clip.as_ref().map(|_| true),
+            clip,
+            ..Default::default()
+        })
+    }
+}
+
+/// The CDP call [`Page::evaluate`] makes: `expression` is evaluated, and if it is a function it
+/// is called with `args` as its arguments. The result is awaited and returned by value.
+pub fn evaluate_params(expression: &str, args: &[serde_json::Value]) -> EvaluateParams {
+    // The newline keeps a trailing `//` comment from swallowing the closing parenthesis
+    let script = format!(
+        "(async () => {{ const value = ({}\n); return typeof value === 'function' ? await value(...{}) : await value; }})()",
+        expression,
+        serde_json::Value::from(args),
+    );
+    let mut params = EvaluateParams::new(script);
+    params.await_promise = Some(true);
+    params.return_by_value = Some(true);
+    params
+}
+
+/// A page (tab) in the session's browser
+#[derive(Clone)]
+pub struct Page {
+    cdp: chromiumoxide::Page,
+    frame_id: String,
+    ai: AiSession,
+}
+
+impl Page {
+    pub(crate) async fn new(cdp: chromiumoxide::Page, ai: AiSession) -> Result<Self, StagehandError> {
+        let frame_id = cdp.mainframe().await?
+            .ok_or_else(|| StagehandError::Cdp("Page has no main frame".to_string()))?
+            .inner()
+            .clone();
+        Ok(Self { cdp, frame_id, ai })
+    }
+
+    /// The underlying chromiumoxide page, for direct CDP control
+    pub fn cdp(&self) -> &chromiumoxide::Page {
+        &self.cdp
+    }
+
+    /// Main frame id, sent as `frame_id` by this page's AI methods
+    pub fn frame_id(&self) -> &str {
+        &self.frame_id
+    }
+
+    pub async fn url(&self) -> Result<Option<String>, StagehandError> {
+        Ok(self.cdp.url().await?)
+    }
+
+    pub async fn title(&self) -> Result<Option<String>, StagehandError> {
+        Ok(self.cdp.get_title().await?)
+    }
+
+    /// Deterministic actions on elements matching `selector` (CSS, or `xpath=...`)
+    pub fn locator(&self, selector: impl Into<String>) -> Locator {
+        Locator::new(self.clone(), selector.into())
+    }
+
+    /// Scroll the page by the given number of CSS pixels
+    pub async fn scroll_by(&self, dx: f64, dy: f64) -> Result<(), StagehandError> {
+        self.cdp.evaluate(format!("window.scrollBy({}, {})", dx, dy)).await?;
+        Ok(())
+    }
+
+    /// Current HTML of the page, including changes made by scripts
+    pub async fn content(&self) -> Result<String, StagehandError> {
+        Ok(self.cdp.content().await?)
+    }
+
+    /// Accessibility tree of the page, without ignored nodes
+    pub async fn accessibility_tree(&self) -> Result<AccessibilityNode, StagehandError> {
+        let tree = self.cdp.execute(chromiumoxide::cdp::browser_protocol::accessibility::GetFullAxTreeParams::default()).await?;
+        accessibility::from_cdp_nodes(&tree.result.nodes)
+            .ok_or_else(|| StagehandError::Cdp("Accessibility tree has no root".to_string()))
+    }
+
+    /// Frame tree of this page
+    pub async fn frames(&self) -> Result<FrameInfo, StagehandError> {
+        let tree = self.cdp.execute(chromiumoxide::cdp::browser_protocol::page::GetFrameTreeParams::default()).await?;
+        Ok(FrameInfo::from(&tree.result.frame_tree))
+    }
+
+    /// Run `expression` in the page's main frame and deserialize its result.
+    ///
+    /// If `expression` evaluates to a function it is called with `args`, one argument per value.
+    /// Promises are awaited, and `undefined` deserializes like `null`.
+    ///
+    /// ```ignore
+    /// let title: String = page.evaluate("document.title", &[]).await?;
+    /// let count: usize = page.evaluate("sel => document.querySelectorAll(sel).length", &[json!("li.result")]).await?;
+    /// ```
+    pub async fn evaluate<T: DeserializeOwned>(&self, expression: &str, args: &[serde_json::Value]) -> Result<T, StagehandError> {
+        self.evaluate_in_context(None, expression, args).await
+    }
+
+    /// [`evaluate`](Self::evaluate) in the frame `frame_id` (see [`frames`](Self::frames))
+    pub async fn evaluate_in_frame<T: DeserializeOwned>(&self, frame_id: &str, expression: &str, args: &[serde_json::Value]) -> Result<T, StagehandError> {
+        let context = self.cdp.frame_execution_context(FrameId::new(frame_id)).await?
+            .ok_or_else(|| StagehandError::Cdp(format!("No JavaScript context for frame {}", frame_id)))?;
+        self.evaluate_in_context(Some(context), expression, args).await
+    }
+
+    async fn evaluate_in_context<T: DeserializeOwned>(
+        &self,
+        context: Option<ExecutionContextId>,
+        expression: &str,
+        args: &[serde_json::Value],
+    ) -> Result<T, StagehandError> {
+        let mut params = evaluate_params(expression, args);
+        params.context_id = context;
+        let result = self.cdp.evaluate_expression(params).await?;
+        serde_json::from_value(result.value().cloned().unwrap_or_default())
+            .map_err(|e| StagehandError::Cdp(format!("Failed to deserialize evaluate result: {}", e)))
+    }
+
+    /// Capture a screenshot of the page, a region, or one element, returning the encoded image
+    pub async fn screenshot(&self, options: ScreenshotOptions) -> Result<Vec<u8>, StagehandError> {
+        let mut params = CaptureScreenshotParams::try_from(&options)?;
+        if let Some(selector) = &options.selector {
+            params.clip = Some(self.element_clip(selector).await?);
+            params.capture_beyond_viewport = Some(true);
+        }
+        if params.clip.is_none() && options.full_page {
+            let mut full_page = chromiumoxide::page::ScreenshotParams::builder()
+                .format(CaptureScreenshotFormat::from(options.format))
+                .full_page(true);
+            if let Some(quality) = params.quality {
+                full_page = full_page.quality(quality);
+            }
+            return Ok(self.cdp.screenshot(full_page.build()).await?);
+        }
+        Ok(self.cdp.screenshot(params).await?)
+    }
+
+    /// Print the page to PDF, returning the file's bytes.
+    ///
+    /// Chrome only prints from headless mode; headful browsers answer with an error.
+    pub async fn pdf(&self, options: PdfOptions) -> Result<Vec<u8>, StagehandError> {
+        Ok(self.cdp.pdf(options.into()).await?)
+    }
+
+    /// Download the PDF the page is showing, with the page's cookies, and return its bytes.
+    ///
+    /// The PDF is requested again with a GET of the page's URL, not read from the response the
+    /// page loaded. A PDF that came from a form POST, a one-time link, or a server that generates
+    /// a different file per request fails or differs from the one shown.
+    pub async fn pdf_bytes(&self) -> Result<Vec<u8>, StagehandError> {
+        use base64::Engine;
+
+        let encoded: String = self.evaluate(
+            "(async () => {
+                const response = await fetch(location.href, { credentials: 'include' });
+                if (!response.ok) throw new Error(`HTTP ${response.status} fetching ${location.href}`);
+                const bytes = new Uint8Array(await response.arrayBuffer());
+                let binary = '';
+                for (let i = 0; i < bytes.length; i += 0x8000) binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+                return btoa(binary);
+            })()",
+            &[],
+        ).await?;
+        base64::engine::general_purpose::STANDARD.decode(encoded)
+            .map_err(|e| StagehandError::Pdf(format!("Invalid PDF download: {}", e)))
+    }
+
+    /// Text of the PDF the page is showing (see [`pdf_text`](crate::pdf_text))
+    #[cfg(feature = "pdf-text")]
+    pub async fn extract_from_pdf(&self) -> Result<String, StagehandError> {
+        let bytes = self.pdf_bytes().await?;
+        crate::spawn_blocking(move || crate::pdf::pdf_text(&bytes)).await
+    }
+
+    /// Document-relative clip around the element matching `selector`
+    async fn element_clip(&self, selector: &str) -> Result<Viewport, StagehandError> {
+        let element = self.find_element(selector).await?;
+        element.scroll_into_view().await?;
+        let bounds = element.bounding_box().await?;
+        // The bounding box is relative to the viewport; clips are relative to the document
+        let viewport = self.cdp.layout_metrics().await?.css_layout_viewport;
+        Ok(Viewport {
+            x: bounds.x + viewport.page_x as f64,
+            y: bounds.y + viewport.page_y as f64,
+            width: bounds.width,
+            height: bounds.height,
+            scale: 1.0,
+        })
+    }
+
+    /// First element matching a CSS selector, or an XPath with an `xpath=` prefix
+    pub(crate) async fn find_element(&self, selector: &str) -> Result<Element, StagehandError> {
+        let element = match Selector::parse(selector) {
+            Selector::XPath(xpath) => self.cdp.find_xpath(xpath).await,
+            Selector::Css(css) => self.cdp.find_element(css).await,
+        };
+        element.map_err(|e| StagehandError::Cdp(format!("No element for selector {}: {}", selector, e)))
+    }
+
+    /// Block, mock, rewrite or record this page's requests until the returned handle is
+    /// detached or dropped. Detaching turns interception off before returning; dropping turns it
+    /// off in the background.
+    pub async fn intercept_network(&self, interceptor: NetworkInterceptor) -> Result<InterceptorHandle, StagehandError> {
+        interceptor.attach(&self.cdp).await
+    }
+
+    /// Record this page's network traffic until [`HarCapture::stop`] returns it as a HAR log
+    pub async fn start_har_capture(&self, options: HarCaptureOptions) -> Result<HarCapture, StagehandError> {
+        HarCapture::start(&self.cdp, options).await
+    }
+
+    /// Collect this page's console messages, uncaught exceptions and failed requests until the
+    /// capture is dropped
+    pub async fn capture_events(&self) -> Result<PageEventCapture, StagehandError> {
+        PageEventCapture::start(&self.cdp).await
+    }
+
+    /// Make this the focused tab
+    pub async fn bring_to_front(&self) -> Result<(), StagehandError> {
+        self.cdp.bring_to_front().await?;
+        Ok(())
+    }
+
+    pub async fn close(self) -> Result<(), StagehandError> {
+        self.cdp.close().await?;
+        Ok(())
+    }
+
+    pub async fn navigate(&self, url: impl Into<String>, mut options: NavigateOptions) -> Result<Pin<Box<dyn Stream<Item = Result<NavigateResponse, StagehandError>> + Send>>, StagehandError> {
+        options.frame_id.get_or_insert_with(|| self.frame_id.clone());
+        self.ai.navigate(url.into(), options).await
+    }
+
+    /// `act` in this page, through the session's action cache and page error context
+    pub async fn act(&self, instruction: impl Into<String>, mut options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+        let (url, frame_url) = if self.ai.has_action_cache() { self.cache_location(options.frame_id.as_deref()).await } else { (None, None) };
+        options.frame_id.get_or_insert_with(|| self.frame_id.clone());
+        self.ai.act(url, frame_url, instruction.into(), options).await
+    }
+
+    pub async fn act_with_action(&self, action: ObservedAction, mut options: ActOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ActResponse, StagehandError>> + Send>>, StagehandError> {
+        options.frame_id.get_or_insert_with(|| self.frame_id.clone());
+        self.ai.act_with_action(action, options).await
+    }
+
+    /// `extract` in this page, through the session's extract cache and page error context
+    pub async fn extract(&self, instruction: impl Into<String>, schema: serde_json::Value, mut options: ExtractOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ExtractResponse, StagehandError>> + Send>>, StagehandError> {
+        let (url, frame_url) = if self.ai.has_extract_cache() { self.cache_location(options.frame_id.as_deref()).await } else { (None, None) };
+        options.frame_id.get_or_insert_with(|| self.frame_id.clone());
+        self.ai.extract(url, frame_url, instruction.into(), schema, options).await
+    }
+
+    pub async fn observe(&self, mut options: ObserveOptions) -> Result<Pin<Box<dyn Stream<Item = Result<ObserveResponse, StagehandError>> + Send>>, StagehandError> {
+        options.frame_id.get_or_insert_with(|| self.frame_id.clone());
+        self.ai.observe(options).await
+    }
+
+    /// Page URL and, for an iframe, frame URL to key cache entries by. CDP frame ids differ on
+    /// every run, so frames are keyed by URL, and the main frame like `Stagehand`'s AI methods
+    /// without a `frame_id`. The page URL is `None`, so nothing is cached, when `frame_id` isn't
+    /// in the page.
+    pub(crate) async fn cache_location(&self, frame_id: Option<&str>) -> (Option<String>, Option<String>) {
+        let url = self.url().await.ok().flatten();
+        let Some(frame_id) = frame_id.filter(|id| *id != self.frame_id) else {
+            return (url, None);
+        };
+        match self.frames().await.ok().and_then(|tree| tree.iter().find(|frame| frame.id == frame_id).map(|frame| frame.url.clone())) {
+            Some(frame_url) => (url, Some(frame_url)),
+            None => (None, None),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Console messages, uncaught exceptions and failed requests of a page.
+//!
+//! Capturing needs the `chromiumoxide` feature. While a [`PageEventCapture`] runs on the
+//! session, errors from `act`, `extract` and the other AI methods include the errors the page
+//! reported while they ran, which often explain why the operation failed.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Something the page reported
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PageEvent {
+    Console(ConsoleMessage),
+    Exception(PageException),
+    RequestFailed(FailedRequest),
+}
+
+/// A `console.*` call, or a browser message such as a blocked mixed-content request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConsoleMessage {
+    /// `log`, `info`, `warning`, `error`, `debug`, ...
+    pub level: String,
+    pub text: String,
+    pub url: Option<String>,
+    pub line: Option<i64>,
+}
+
+/// An exception no script caught
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageException {
+    /// Message, with the stack trace when the browser provides one
+    pub message: String,
+    pub url: Option<String>,
+    pub line: i64,
+    pub column: i64,
+}
+
+/// A request that failed without a response, e.g. a DNS or CORS error
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FailedRequest {
+    pub method: String,
+    pub url: String,
+    /// e.g. `net::ERR_NAME_NOT_RESOLVED`
+    pub error: String,
+}
+
+impl PageEvent {
+    /// Whether the event indicates a problem: console errors, exceptions and failed requests
+    pub fn is_error(&self) -> bool {
+        match self {
+            PageEvent::Console(message) => matches!(message.level.as_str(), "error" | "assert"),
+            PageEvent::Exception(_) | PageEvent::RequestFailed(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for PageEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PageEvent::Console(message) => {
+                write!(f, "console.{}: {}", message.level, message.text)?;
+                match (&message.url, message.line) {
+                    (Some(url), Some(line)) => write!(f, " ({}:{})", url, line),
+                    (Some(url), None) => write!(f, " ({})", url),
+                    _ => Ok(()),
+                }
+            }
+            PageEvent::Exception(exception) => {
+                write!(f, "Uncaught {}", exception.message)?;
+                match &exception.url {
+                    Some(url) => write!(f, " ({}:{}:{})", url, exception.line, exception.column),
+                    None => Ok(()),
+                }
+            }
+            PageEvent::RequestFailed(request) => write!(f, "Request failed: {} {} ({})", request.method, request.url, request.error),
+        }
+    }
+}
+
+#[cfg(feature = "chromiumoxide")]
+pub use capture::{PageEventCapture, PageEventStream};
+
+#[cfg(feature = "chromiumoxide")]
+mod capture {
+    use chromiumoxide::cdp::browser_protocol::log::{self, EventEntryAdded, LogEntrySource};
+    use chromiumoxide::cdp::browser_protocol::network::{self, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent};
+    use chromiumoxide::cdp::js_protocol::runtime::{EventConsoleApiCalled, EventExceptionThrown, RemoteObject};
+    use futures::{Stream, StreamExt};
+    use std::collections::{HashMap, VecDeque};
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::{ErrorAnnotator, StagehandError};
+
+    /// How many recent errors are kept for error context
+    const RECENT_ERRORS: usize = 10;
+
+    /// Events from a page as they happen
+    pub type PageEventStream = Pin<Box<dyn Stream<Item = PageEvent> + Send>>;
+
+    enum CdpEvent {
+        ConsoleApiCalled(Arc<EventConsoleApiCalled>),
+        ExceptionThrown(Arc<EventExceptionThrown>),
+        EntryAdded(Arc<EventEntryAdded>),
+        RequestWillBeSent(Arc<EventRequestWillBeSent>),
+        LoadingFinished(Arc<EventLoadingFinished>),
+        LoadingFailed(Arc<EventLoadingFailed>),
+    }
+
+    #[derive(Default)]
+    struct Shared {
+        /// With the number of errors before each, so an operation can pick the errors since it started
+        recent_errors: VecDeque<(u64, PageEvent)>,
+        errors_seen: u64,
+        subscribers: Vec<async_channel::Sender<PageEvent>>,
+    }
+
+    /// Collects a page's events until dropped, for subscribers and for error context
+    pub struct PageEventCapture {
+        task: JoinHandle<()>,
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl fmt::Debug for PageEventCapture {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let shared = self.shared.lock().unwrap();
+            f.debug_struct("PageEventCapture")
+                .field("recent_errors", &shared.recent_errors.len())
+                .field("subscribers", &shared.subscribers.len())
+                .finish()
+        }
+    }
+
+    impl PageEventCapture {
+        pub async fn start(page: &chromiumoxide::Page) -> Result<Self, StagehandError> {
+            let events: Vec<Pin<Box<dyn Stream<Item = CdpEvent> + Send>>> = vec![
+                Box::pin(page.event_listener::<EventConsoleApiCalled>().await?.map(CdpEvent::ConsoleApiCalled)),
+                Box::pin(page.event_listener::<EventExceptionThrown>().await?.map(CdpEvent::ExceptionThrown)),
+                Box::pin(page.event_listener::<EventEntryAdded>().await?.map(CdpEvent::EntryAdded)),
+                Box::pin(page.event_listener::<EventRequestWillBeSent>().await?.map(CdpEvent::RequestWillBeSent)),
+                Box::pin(page.event_listener::<EventLoadingFinished>().await?.map(CdpEvent::LoadingFinished)),
+                Box::pin(page.event_listener::<EventLoadingFailed>().await?.map(CdpEvent::LoadingFailed)),
+            ];
+            page.execute(log::EnableParams::default()).await?;
+            page.execute(network::EnableParams::default()).await?;
+
+            let shared = Arc::new(Mutex::new(Shared::default()));
+            let task = {
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    // Method and URL of requests in flight, which `loadingFailed` does not repeat
+                    let mut requests = HashMap::new();
+                    let mut events = futures::stream::select_all(events);
+                    while let Some(event) = events.next().await {
+                        if let Some(event) = page_event(event, &mut requests) {
+                            publish(&shared, event);
+                        }
+                    }
+                })
+            };
+            Ok(Self { task, shared })
+        }
+
+        /// Events from now on. The stream ends when the capture stops.
+        pub fn subscribe(&self) -> PageEventStream {
+            let (sender, receiver) = async_channel::unbounded();
+            self.shared.lock().unwrap().subscribers.push(sender);
+            Box::pin(receiver)
+        }
+
+        /// The most recent console errors, exceptions and failed requests, oldest first
+        pub fn recent_errors(&self) -> Vec<PageEvent> {
+            self.shared.lock().unwrap().recent_errors.iter().map(|(_, event)| event.clone()).collect()
+        }
+
+        /// Called as an AI operation starts. The returned annotator appends the errors the page
+        /// reported since then to an error's message, at the time the error happens.
+        pub(crate) fn error_annotators(&self) -> impl Fn() -> ErrorAnnotator + Send + Sync + 'static {
+            let shared = self.shared.clone();
+            move || {
+                let shared = shared.clone();
+                let since = shared.lock().unwrap().errors_seen;
+                Arc::new(move |err| {
+                    let recent: Vec<_> = shared.lock().unwrap().recent_errors.iter()
+                        .filter(|(seq, _)| *seq >= since)
+                        .map(|(_, event)| event.clone())
+                        .collect();
+                    match error_context(&recent) {
+                        Some(context) => err.with_context(&context),
+                        None => err,
+                    }
+                })
+            }
+        }
+    }
+
+    impl Drop for PageEventCapture {
+        fn drop(&mut self) {
+            self.task.abort();
+            // Closing the senders ends subscriber streams
+            self.shared.lock().unwrap().subscribers.clear();
+        }
+    }
+
+    /// Error message context listing `events`, or `None` when there are none
+    fn error_context(events: &[PageEvent]) -> Option<String> {
+        if events.is_empty() {
+            return None;
+        }
+        let lines: Vec<_> = events.iter().map(|event| format!("  {}", event)).collect();
+        Some(format!("Recent page errors:\n{}", lines.join("\n")))
+    }
+
+    fn publish(shared: &Mutex<Shared>, event: PageEvent) {
+        let mut shared = shared.lock().unwrap();
+        if event.is_error() {
+            if shared.recent_errors.len() == RECENT_ERRORS {
+                shared.recent_errors.pop_front();
+            }
+            let seq = shared.errors_seen;
+            shared.recent_errors.push_back((seq, event.clone()));
+            shared.errors_seen += 1;
+        }
+        shared.subscribers.retain(|subscriber| subscriber.try_send(event.clone()).is_ok());
+    }
+
+    fn page_event(event: CdpEvent, requests: &mut HashMap<String, (String, String)>) -> Option<PageEvent> {
+        match event {
+            CdpEvent::ConsoleApiCalled(event) => {
+                let frame = event.stack_trace.as_ref().and_then(|trace| trace.call_frames.first());
+                Some(PageEvent::Console(ConsoleMessage {
+                    level: event.r#type.as_ref().to_string(),
+                    text: event.args.iter().map(remote_object_text).collect::<Vec<_>>().join(" "),
+                    url: frame.map(|frame| frame.url.clone()).filter(|url| !url.is_empty()),
+                    // CDP lines are zero-based
+                    line: frame.map(|frame| frame.line_number + 1),
+                }))
+            }
+            CdpEvent::ExceptionThrown(event) => {
+                let details = &event.exception_details;
+                let message = details.exception.as_ref()
+                    .and_then(|exception| exception.description.clone())
+                    .unwrap_or_else(|| details.text.clone());
+                Some(PageEvent::Exception(PageException {
+                    message,
+                    url: details.url.clone().filter(|url| !url.is_empty()),
+                    line: details.line_number + 1,
+                    column: details.column_number + 1,
+                }))
+            }
+            // Failed requests come from `loadingFailed`, with their method
+            CdpEvent::EntryAdded(event) if event.entry.source == LogEntrySource::Network => None,
+            CdpEvent::EntryAdded(event) => Some(PageEvent::Console(ConsoleMessage {
+                level: event.entry.level.as_ref().to_string(),
+                text: event.entry.text.clone(),
+                url: event.entry.url.clone(),
+                line: event.entry.line_number.map(|line| line + 1),
+            })),
+            CdpEvent::RequestWillBeSent(event) => {
+                requests.insert(event.request_id.inner().clone(), (event.request.method.clone(), event.request.url.clone()));
+                None
+            }
+            CdpEvent::LoadingFinished(event) => {
+                requests.remove(event.request_id.inner());
+                None
+            }
+            CdpEvent::LoadingFailed(event) => {
+                let (method, url) = requests.remove(event.request_id.inner())?;
+                // Requests the page itself aborted, e.g. on navigation, are not failures
+                if event.canceled == Some(true) {
+                    return None;
+                }
+                Some(PageEvent::RequestFailed(FailedRequest { method, url, error: event.error_text.clone() }))
+            }
+        }
+    }
+
+    /// How the console prints an argument: strings as-is, other values as JSON or their description
+    fn remote_object_text(object: &RemoteObject) -> String {
+        match (&object.value, &object.unserializable_value, &object.description) {
+            (Some(serde_json::Value::String(text)), _, _) => text.clone(),
+            (Some(value), _, _) => value.to_string(),
+            (None, Some(value), _) => value.inner().clone(),
+            (None, None, Some(description)) => description.clone(),
+            (None, None, None) => object.r#type.as_ref().to_string(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! PDF generation from a page and text extraction from PDF files.
+//!
+//! [`pdf_text`], enabled with the `pdf-text` feature, extracts text with
+//! [lopdf](https://crates.io/crates/lopdf). It is best-effort: encrypted PDFs and text drawn as
+//! images are not supported.
+
+#[cfg(feature = "pdf-text")]
+use crate::StagehandError;
+
+/// Options for `pdf`, mirroring CDP `Page.printToPDF`. Sizes are in inches.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct PdfOptions {
+    pub landscape: bool,
+    /// Print background graphics
+    pub print_background: bool,
+    /// Scale of the page rendering, between 0.1 and 2 (default 1)
+    pub scale: Option<f64>,
+    /// Paper width and height (default US Letter, 8.5 x 11)
+    pub paper_size: Option<(f64, f64)>,
+    /// Top, right, bottom and left margins (default about 0.4 each)
+    pub margins: Option<(f64, f64, f64, f64)>,
+    /// Pages to print, e.g. `1-5, 8`
+    pub page_ranges: Option<String>,
+    /// HTML templates for the header and footer; printing them is enabled when either is set
+    pub header_template: Option<String>,
+    pub footer_template: Option<String>,
+    /// Use the page size from the page's CSS `@page` rule
+    pub prefer_css_page_size: bool,
+}
+
+impl PdfOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn landscape(mut self, landscape: bool) -> Self {
+        self.landscape = landscape;
+        self
+    }
+
+    pub fn print_background(mut self, print_background: bool) -> Self {
+        self.print_background = print_background;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    pub fn paper_size(mut self, width: f64, height: f64) -> Self {
+        self.paper_size = Some((width, height));
+        self
+    }
+
+    pub fn margins(mut self, top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        self.margins = Some((top, right, bottom, left));
+        self
+    }
+
+    pub fn page_ranges(mut self, page_ranges: impl Into<String>) -> Self {
+        self.page_ranges = Some(page_ranges.into());
+        self
+    }
+
+    pub fn header_template(mut self, html: impl Into<String>) -> Self {
+        self.header_template = Some(html.into());
+        self
+    }
+
+    pub fn footer_template(mut self, html: impl Into<String>) -> Self {
+        self.footer_template = Some(html.into());
+        self
+    }
+
+    pub fn prefer_css_page_size(mut self, prefer_css_page_size: bool) -> Self {
+        self.prefer_css_page_size = prefer_css_page_size;
+        self
+    }
+}
+
+#[cfg(feature = "chromiumoxide")]
+impl From<PdfOptions> for chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams {
+    fn from(options: PdfOptions) -> Self {
+        let header_footer = options.header_template.is_some() || options.footer_template.is_some();
+        Self {
+            landscape: Some(options.landscape),
+            print_background: Some(options.print_background),
+            scale: options.scale,
+            paper_width: options.paper_size.map(|(width, _)| width),
+            paper_height: options.paper_size.map(|(_, height)| height),
+            margin_top: options.margins.map(|m| m.0),
+            margin_right: options.margins.map(|m| m.1),
+            margin_bottom: options.margins.map(|m| m.2),
+            margin_left: options.margins.map(|m| m.3),
+            page_ranges: options.page_ranges,
+            display_header_footer: Some(header_footer),
+            header_template: options.header_template,
+            footer_template: options.footer_template,
+            prefer_css_page_size: Some(options.prefer_css_page_size),
+            ..Default::default()
+        }
+    }
+}
+
+/// Bytes a single stream, or the content of a single page, may inflate to, so a small
+/// compressed stream can't exhaust memory
+#[cfg(feature = "pdf-text")]
+const MAX_DECODED_STREAM: usize = 64 * 1024 * 1024;
+
+/// Text of a PDF, pages separated by a blank line
+#[cfg(feature = "pdf-text")]
+pub fn pdf_text(bytes: &[u8]) -> Result<String, StagehandError> {
+    if !bytes.starts_with(b"%PDF") {
+        return Err(StagehandError::Pdf("Not a PDF file".to_string()));
+    }
+    let options = lopdf::LoadOptions { max_decompressed_size: Some(MAX_DECODED_STREAM), ..Default::default() };
+    let document = lopdf::Document::load_mem_with_options(bytes, options)
+        .map_err(|e| StagehandError::Pdf(format!("Failed to parse PDF: {}", e)))?;
+    if document.is_encrypted() {
+        return Err(StagehandError::Pdf("Encrypted PDFs are not supported".to_string()));
+    }
+
+    let mut pages = Vec::new();
+    for page in document.get_pages().into_keys() {
+        let text = document.extract_text_with_limit(&[page], MAX_DECODED_STREAM)
+            .map_err(|e| StagehandError::Pdf(format!("Failed to extract text of page {}: {}", page, e)))?;
+        // lopdf ends each text object with a newline; drop the blank and trailing space it leaves
+        let lines: Vec<_> = text.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect();
+        if !lines.is_empty() {
+            pages.push(lines.join("\n"));
+        }
+    }
+    Ok(pages.join("\n\n"))
+}
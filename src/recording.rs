@@ -0,0 +1,67 @@
+//! Session recordings and HTML replays.
+//!
+//! Browserbase records sessions as [rrweb](https://github.com/rrweb-io/rrweb) events. The replay
+//! file embeds the events but not the player: it loads `rrweb-player` from jsDelivr when opened,
+//! so viewing it needs network access and trusts that CDN.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::StagehandError;
+
+const RRWEB_PLAYER_VERSION: &str = "1.0.0-alpha.4";
+
+/// A single rrweb event from a session recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingEvent {
+    /// rrweb event type (e.g. 2 for a full snapshot, 3 for an incremental snapshot)
+    #[serde(rename = "type")]
+    pub event_type: i64,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: i64,
+    #[serde(default)]
+    pub data: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+/// Render an HTML page that replays `events` with rrweb-player.
+///
+/// The page is not self-contained: it loads the player script and stylesheet from jsDelivr, so it
+/// shows nothing when opened offline or under a content security policy that blocks the CDN.
+pub fn cdn_replay_html(events: &[RecordingEvent], title: &str) -> Result<String, StagehandError> {
+    let events_json = serde_json::to_string(events)
+        .map_err(|e| StagehandError::Api(format!("Failed to serialize recording: {}", e)))?
+        // `<` only occurs inside JSON strings, so escaping it keeps `</script>` in page content
+        // from closing the script tag
+        .replace('<', "\\u003c");
+    let title = title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/rrweb-player@{version}/dist/style.css">
+<script src="https://cdn.jsdelivr.net/npm/rrweb-player@{version}/dist/index.js"></script>
+</head>
+<body>
+<div id="player"></div>
+<script>
+const events = {events_json};
+new rrwebPlayer({{ target: document.getElementById("player"), props: {{ events, autoPlay: false }} }});
+</script>
+</body>
+</html>
+"#,
+        version = RRWEB_PLAYER_VERSION,
+    ))
+}
+
+/// Write an HTML replay of `events` to `path`. See [`cdn_replay_html`] for its CDN dependency.
+pub fn write_cdn_replay_html(events: &[RecordingEvent], title: &str, path: impl AsRef<Path>) -> Result<(), StagehandError> {
+    std::fs::write(path, cdn_replay_html(events, title)?)?;
+    Ok(())
+}
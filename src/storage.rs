@@ -0,0 +1,241 @@
+//! Cookie and web storage import/export in Playwright's `storageState` format.
+//!
+//! A [`StorageState`] saved from one session can bootstrap another, e.g. to start a Browserbase
+//! session already logged in without a Context. Capturing and applying a state needs the
+//! `chromiumoxide` feature.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::StagehandError;
+
+/// Cookies plus `localStorage` and `sessionStorage` by origin.
+///
+/// The JSON matches Playwright's `storageState`, so files load in either direction.
+/// `sessionStorage` is an extension that Playwright ignores.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageState {
+    #[serde(default)]
+    pub cookies: Vec<StorageCookie>,
+    #[serde(default)]
+    pub origins: Vec<OriginStorage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    /// Seconds since the Unix epoch, or -1 for a session cookie
+    pub expires: f64,
+    pub http_only: bool,
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    Strict,
+    #[default]
+    Lax,
+    None,
+}
+
+/// Web storage of one origin, e.g. `https://app.example.com`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginStorage {
+    pub origin: String,
+    #[serde(default)]
+    pub local_storage: Vec<StorageItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_storage: Vec<StorageItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageItem {
+    pub name: String,
+    pub value: String,
+}
+
+impl StorageState {
+    /// Read a state saved by [`write_to`](Self::write_to) or Playwright's `storageState({ path })`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, StagehandError> {
+        serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| StagehandError::Api(format!("Failed to parse storage state: {}", e)))
+    }
+
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), StagehandError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| StagehandError::Api(format!("Failed to serialize storage state: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "chromiumoxide")]
+mod cdp {
+    use chromiumoxide::cdp::browser_protocol::network::{Cookie, CookieParam, CookieSameSite, TimeSinceEpoch};
+    use chromiumoxide::cdp::browser_protocol::page::{
+        AddScriptToEvaluateOnNewDocumentParams, EventFrameNavigated, RemoveScriptToEvaluateOnNewDocumentParams,
+    };
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::interceptor::{MockResponse, NetworkInterceptor};
+
+    /// Reads the web storage of the page's origin; `null` where storage is not accessible
+    const READ_STORAGE_JS: &str = r#"(() => {
+        try {
+            const items = storage => Array.from({ length: storage.length }, (_, i) => storage.key(i))
+                .map(name => ({ name, value: storage.getItem(name) }));
+            return { origin: location.origin, localStorage: items(localStorage), sessionStorage: items(sessionStorage) };
+        } catch (e) {
+            return null;
+        }
+    })()"#;
+
+    impl From<&Cookie> for StorageCookie {
+        fn from(cookie: &Cookie) -> Self {
+            StorageCookie {
+                name: cookie.name.clone(),
+                value: cookie.value.clone(),
+                domain: cookie.domain.clone(),
+                path: cookie.path.clone(),
+                expires: if cookie.session { -1.0 } else { cookie.expires },
+                http_only: cookie.http_only,
+                secure: cookie.secure,
+                same_site: match cookie.same_site {
+                    Some(CookieSameSite::Strict) => SameSite::Strict,
+                    Some(CookieSameSite::None) => SameSite::None,
+                    Some(CookieSameSite::Lax) | None => SameSite::Lax,
+                },
+            }
+        }
+    }
+
+    impl From<&StorageCookie> for CookieParam {
+        fn from(cookie: &StorageCookie) -> Self {
+            let mut param = CookieParam::new(cookie.name.clone(), cookie.value.clone());
+            param.domain = Some(cookie.domain.clone());
+            param.path = Some(cookie.path.clone());
+            param.secure = Some(cookie.secure);
+            param.http_only = Some(cookie.http_only);
+            param.same_site = Some(match cookie.same_site {
+                SameSite::Strict => CookieSameSite::Strict,
+                SameSite::Lax => CookieSameSite::Lax,
+                SameSite::None => CookieSameSite::None,
+            });
+            param.expires = (cookie.expires >= 0.0).then(|| TimeSinceEpoch::new(cookie.expires));
+            param
+        }
+    }
+
+    fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, StagehandError> {
+        serde_json::to_string(value)
+            .map_err(|e| StagehandError::Api(format!("Failed to serialize storage state: {}", e)))
+    }
+
+    impl StorageState {
+        /// All cookies of the browser, and the web storage of the origins its pages are on
+        pub async fn capture(browser: &chromiumoxide::Browser) -> Result<Self, StagehandError> {
+            let cookies = browser.get_cookies().await?.iter().map(StorageCookie::from).collect();
+            let mut origins: Vec<OriginStorage> = Vec::new();
+            for page in browser.pages().await? {
+                let storage: Option<OriginStorage> = page.evaluate(READ_STORAGE_JS).await?.into_value()
+                    .map_err(|e| StagehandError::Cdp(format!("Failed to read web storage: {}", e)))?;
+                // Pages like `about:blank` have an opaque origin
+                if let Some(storage) = storage
+                    && storage.origin.starts_with("http")
+                    && !origins.iter().any(|o| o.origin == storage.origin)
+                {
+                    origins.push(storage);
+                }
+            }
+            Ok(Self { cookies, origins })
+        }
+
+        /// Set the cookies in `browser`, and the web storage of each origin.
+        ///
+        /// `localStorage` is written in a temporary page that visits each origin with every
+        /// request answered by an empty page, so no site code runs and `page` stays where it is.
+        /// `sessionStorage` belongs to a tab, so it is written into `page` the next time it loads
+        /// each origin.
+        pub async fn apply(&self, browser: &chromiumoxide::Browser, page: &chromiumoxide::Page) -> Result<(), StagehandError> {
+            if !self.cookies.is_empty() {
+                browser.set_cookies(self.cookies.iter().map(CookieParam::from).collect()).await?;
+            }
+            self.write_local_storage(browser).await?;
+            self.seed_session_storage(page).await
+        }
+
+        async fn write_local_storage(&self, browser: &chromiumoxide::Browser) -> Result<(), StagehandError> {
+            let origins: Vec<_> = self.origins.iter().filter(|o| !o.local_storage.is_empty()).collect();
+            if origins.is_empty() {
+                return Ok(());
+            }
+
+            let page = browser.new_page("about:blank").await?;
+            let written = async {
+                let blank = NetworkInterceptor::new()
+                    .fulfill("*", MockResponse::new(200, "<html></html>").header("Content-Type", "text/html"))
+                    .attach(&page)
+                    .await?;
+                for origin in origins {
+                    page.goto(origin.origin.as_str()).await?;
+                    page.evaluate(format!(
+                        "(items => {{ for (const {{ name, value }} of items) localStorage.setItem(name, value); }})({})",
+                        to_json(&origin.local_storage)?,
+                    )).await?;
+                }
+                blank.detach().await?;
+                Ok::<_, StagehandError>(())
+            }.await;
+            page.close().await?;
+            written
+        }
+
+        async fn seed_session_storage(&self, page: &chromiumoxide::Page) -> Result<(), StagehandError> {
+            let origins: Vec<_> = self.origins.iter().filter(|o| !o.session_storage.is_empty()).collect();
+            if origins.is_empty() {
+                return Ok(());
+            }
+
+            let mut navigations = page.event_listener::<EventFrameNavigated>().await?;
+            let mut scripts = Vec::new();
+            for origin in origins {
+                let source = format!(
+                    "((origin, items) => {{
+                        if (window === window.top && location.origin === origin)
+                            for (const {{ name, value }} of items) sessionStorage.setItem(name, value);
+                    }})({}, {})",
+                    to_json(&origin.origin)?,
+                    to_json(&origin.session_storage)?,
+                );
+                let added = page.execute(AddScriptToEvaluateOnNewDocumentParams::new(source)).await?;
+                scripts.push((origin.origin.clone(), added.result.identifier.clone()));
+            }
+
+            // Each script runs once: after the page's first load of its origin, later loads keep
+            // whatever the site did with the storage
+            let page = page.clone();
+            tokio::spawn(async move {
+                while let Some(event) = navigations.next().await {
+                    if event.frame.parent_id.is_some() {
+                        continue;
+                    }
+                    if let Some(i) = scripts.iter().position(|(origin, _)| *origin == event.frame.security_origin) {
+                        let (_, identifier) = scripts.swap_remove(i);
+                        let _ = page.execute(RemoveScriptToEvaluateOnNewDocumentParams::new(identifier)).await;
+                    }
+                    if scripts.is_empty() {
+                        break;
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+}
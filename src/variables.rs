@@ -0,0 +1,195 @@
+//! Variables for `act` instructions, including secrets that are kept out of logs and errors.
+//!
+//! Instructions reference variables with `%name%` placeholders, which the server substitutes
+//! after LLM inference, so the values themselves are never sent to the model.
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+const MASK: &str = "********";
+
+/// A variable value that is masked in `Debug` output.
+///
+/// Secret values are also redacted back to their `%name%` placeholder in the log lines, errors
+/// and returned actions of the `act` stream. Anything recorded from the page itself, such as
+/// session recordings or HAR captures, is not redacted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Access the underlying value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString({:?})", MASK)
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+/// Value of an `act` variable
+#[derive(Clone, PartialEq, Eq)]
+pub enum VariableValue {
+    Plain(String),
+    Secret(SecretString),
+}
+
+impl VariableValue {
+    pub fn expose(&self) -> &str {
+        match self {
+            VariableValue::Plain(s) => s,
+            VariableValue::Secret(s) => s.expose_secret(),
+        }
+    }
+
+    pub fn is_secret(&self) -> bool {
+        matches!(self, VariableValue::Secret(_))
+    }
+}
+
+impl fmt::Debug for VariableValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableValue::Plain(s) => f.debug_tuple("Plain").field(s).finish(),
+            VariableValue::Secret(s) => f.debug_tuple("Secret").field(s).finish(),
+        }
+    }
+}
+
+// The server needs the real value to substitute it into the action
+impl Serialize for VariableValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.expose())
+    }
+}
+
+impl From<String> for VariableValue {
+    fn from(value: String) -> Self {
+        VariableValue::Plain(value)
+    }
+}
+
+impl From<&str> for VariableValue {
+    fn from(value: &str) -> Self {
+        VariableValue::Plain(value.to_string())
+    }
+}
+
+impl From<SecretString> for VariableValue {
+    fn from(value: SecretString) -> Self {
+        VariableValue::Secret(value)
+    }
+}
+
+/// Replace each secret value in `text` with its `%name%` placeholder
+pub(crate) fn redact(text: &str, secrets: &[(String, String)]) -> String {
+    let mut redacted = text.to_string();
+    for (name, value) in secrets {
+        if !value.is_empty() {
+            redacted = redacted.replace(value.as_str(), &format!("%{}%", name));
+        }
+    }
+    redacted
+}
+
+/// Compile-time check that every `%name%` placeholder in `template` is one of `names`.
+///
+/// Used by [`templated_instruction!`](crate::templated_instruction); panics (failing the build
+/// when evaluated in a `const`) on the first placeholder without a variable.
+#[doc(hidden)]
+pub const fn check_placeholders(template: &str, names: &[&str]) {
+    let bytes = template.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            i += 1;
+            continue;
+        }
+
+        let start = i + 1;
+        let mut end = start;
+        while end < bytes.len() && is_name_byte(bytes[end]) {
+            end += 1;
+        }
+        if end == start || end >= bytes.len() || bytes[end] != b'%' {
+            // A literal percent sign, not a placeholder
+            i += 1;
+            continue;
+        }
+
+        if !contains_name(names, bytes, start, end) {
+            panic!("templated instruction has a %placeholder% without a matching variable");
+        }
+        i = end + 1;
+    }
+}
+
+const fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+const fn contains_name(names: &[&str], bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut n = 0;
+    while n < names.len() {
+        let name = names[n].as_bytes();
+        if name.len() == end - start {
+            let mut j = 0;
+            while j < name.len() && name[j] == bytes[start + j] {
+                j += 1;
+            }
+            if j == name.len() {
+                return true;
+            }
+        }
+        n += 1;
+    }
+    false
+}
+
+/// Build a `%name%`-templated instruction and its variables for `act`.
+///
+/// Expands to `(String, HashMap<String, VariableValue>)`. Every placeholder in the template must
+/// have a matching variable, or compilation fails.
+///
+/// ```
+/// use stagehand_sdk::{templated_instruction, SecretString};
+///
+/// let (instruction, variables) = templated_instruction!(
+///     "Log in as %username% with password %password%",
+///     username = "alice",
+///     password = SecretString::new("hunter2"),
+/// );
+/// assert_eq!(variables.len(), 2);
+/// ```
+///
+/// ```compile_fail
+/// use stagehand_sdk::templated_instruction;
+///
+/// // `%password%` has no variable
+/// let (instruction, variables) = templated_instruction!("Type %password%", username = "alice");
+/// ```
+#[macro_export]
+macro_rules! templated_instruction {
+    ($template:literal $(, $name:ident = $value:expr)* $(,)?) => {{
+        const _: () = $crate::variables::check_placeholders($template, &[$(stringify!($name)),*]);
+        #[allow(unused_mut)]
+        let mut variables = ::std::collections::HashMap::<::std::string::String, $crate::VariableValue>::new();
+        $(variables.insert(stringify!($name).to_string(), $crate::VariableValue::from($value));)*
+        (::std::string::String::from($template), variables)
+    }};
+}
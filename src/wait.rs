@@ -0,0 +1,128 @@
+//! Client-side wait conditions for a page, complementing the server's DOM settling
+//! (`dom_settle_timeout_ms`).
+//!
+//! Enabled with the `chromiumoxide` feature.
+
+use chromiumoxide::cdp::browser_protocol::network::{EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent};
+use futures::StreamExt;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::frames::glob_match;
+use crate::page::Page;
+use crate::{LoadState, StagehandError};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long the network must be quiet for `NetworkIdle`
+const NETWORK_IDLE_DURATION: Duration = Duration::from_millis(500);
+
+impl Page {
+    /// Wait until the page reaches `state`.
+    ///
+    /// `NetworkIdle` waits for `Load` and then for no requests in flight for 500 ms.
+    pub async fn wait_for_load_state(&self, state: LoadState, timeout: Duration) -> Result<(), StagehandError> {
+        with_timeout(timeout, format!("load state {:?}", state), async {
+            match state {
+                LoadState::DomContentLoaded => self.wait_for_ready_state(&["interactive", "complete"]).await,
+                LoadState::Load => self.wait_for_ready_state(&["complete"]).await,
+                LoadState::NetworkIdle => self.wait_for_network_idle().await,
+            }
+        })
+        .await
+    }
+
+    /// Wait until the page URL matches the glob `pattern` (see [`FrameInfo::find_by_url`](crate::FrameInfo::find_by_url))
+    pub async fn wait_for_url(&self, pattern: &str, timeout: Duration) -> Result<(), StagehandError> {
+        self.wait_for(
+            |page| async move { Ok(page.url().await?.is_some_and(|url| glob_match(pattern, &url))) },
+            timeout,
+        )
+        .await
+        .map_err(|e| match e {
+            StagehandError::Timeout(_) => StagehandError::Timeout(format!("Timed out after {:?} waiting for URL {}", timeout, pattern)),
+            other => other,
+        })
+    }
+
+    /// Wait until an element matches `selector` (CSS, or `xpath=...`).
+    ///
+    /// Like [`Locator::wait_for`](crate::Locator::wait_for), only an empty match is waited on;
+    /// an invalid selector or a closed page fails right away.
+    pub async fn wait_for_selector(&self, selector: &str, timeout: Duration) -> Result<(), StagehandError> {
+        self.locator(selector).timeout(timeout).wait_for().await
+    }
+
+    /// Wait until `predicate` returns `true`, polling every 100 ms.
+    ///
+    /// ```ignore
+    /// page.wait_for(|page| async move {
+    ///     Ok(page.content().await?.contains("Order confirmed"))
+    /// }, Duration::from_secs(10)).await?;
+    /// ```
+    pub async fn wait_for<F, Fut>(&self, mut predicate: F, timeout: Duration) -> Result<(), StagehandError>
+    where
+        F: FnMut(Page) -> Fut,
+        Fut: Future<Output = Result<bool, StagehandError>>,
+    {
+        with_timeout(timeout, "condition".to_string(), async {
+            while !predicate(self.clone()).await? {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn wait_for_ready_state(&self, states: &[&str]) -> Result<(), StagehandError> {
+        loop {
+            let ready_state: String = self.cdp().evaluate("document.readyState").await?.into_value()
+                .map_err(|e| StagehandError::Cdp(e.to_string()))?;
+            if states.contains(&ready_state.as_str()) {
+                return Ok(());
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn wait_for_network_idle(&self) -> Result<(), StagehandError> {
+        enum NetworkEvent {
+            Started(String),
+            Done(String),
+        }
+
+        // Subscribe before waiting for load so no request is missed
+        let started = self.cdp().event_listener::<EventRequestWillBeSent>().await?
+            .map(|e| NetworkEvent::Started(e.request_id.inner().clone()));
+        let finished = self.cdp().event_listener::<EventLoadingFinished>().await?
+            .map(|e| NetworkEvent::Done(e.request_id.inner().clone()));
+        let failed = self.cdp().event_listener::<EventLoadingFailed>().await?
+            .map(|e| NetworkEvent::Done(e.request_id.inner().clone()));
+        let mut events = futures::stream::select(started, futures::stream::select(finished, failed));
+
+        self.wait_for_ready_state(&["complete"]).await?;
+
+        // Requests already in flight when we subscribed are not tracked
+        let mut in_flight = HashSet::new();
+        loop {
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(NetworkEvent::Started(id)) => {
+                        in_flight.insert(id);
+                    }
+                    Some(NetworkEvent::Done(id)) => {
+                        in_flight.remove(&id);
+                    }
+                    None => return Err(StagehandError::Cdp("Page closed while waiting for network idle".to_string())),
+                },
+                _ = tokio::time::sleep(NETWORK_IDLE_DURATION), if in_flight.is_empty() => return Ok(()),
+            }
+        }
+    }
+}
+
+async fn with_timeout<T>(timeout: Duration, what: String, future: impl Future<Output = Result<T, StagehandError>>) -> Result<T, StagehandError> {
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_| StagehandError::Timeout(format!("Timed out after {:?} waiting for {}", timeout, what)))?
+}
@@ -0,0 +1,51 @@
+use stagehand_sdk::AccessibilityNode;
+
+fn node(role: &str, name: &str, children: Vec<AccessibilityNode>) -> AccessibilityNode {
+    AccessibilityNode {
+        role: role.into(),
+        name: name.into(),
+        value: None,
+        description: None,
+        backend_node_id: None,
+        children,
+    }
+}
+
+#[test]
+fn test_accessibility_outline() {
+    let mut search = node("textbox", "Search", vec![]);
+    search.value = Some("shoes".into());
+    let tree = node("RootWebArea", "Shop", vec![node("navigation", "", vec![node("link", "Home", vec![])]), search]);
+
+    let roles: Vec<_> = tree.iter().map(|n| n.role.as_str()).collect();
+    assert_eq!(roles, vec!["RootWebArea", "navigation", "link", "textbox"]);
+    assert_eq!(
+        tree.to_outline(),
+        "RootWebArea \"Shop\"\n  navigation\n    link \"Home\"\n  textbox \"Search\" = \"shoes\"\n",
+    );
+}
+
+#[cfg(feature = "chromiumoxide")]
+#[test]
+fn test_accessibility_tree_from_cdp_nodes() {
+    use chromiumoxide::cdp::browser_protocol::accessibility::AxNode;
+
+    let nodes: Vec<AxNode> = serde_json::from_value(serde_json::json!([
+        { "nodeId": "1", "ignored": false, "role": { "type": "role", "value": "RootWebArea" },
+          "name": { "type": "computedString", "value": "Example" }, "childIds": ["2"], "backendDOMNodeId": 10 },
+        { "nodeId": "2", "ignored": true, "parentId": "1", "childIds": ["3", "4"] },
+        { "nodeId": "3", "ignored": false, "parentId": "2", "role": { "type": "role", "value": "heading" },
+          "name": { "type": "computedString", "value": "Example Domain" }, "childIds": [] },
+        { "nodeId": "4", "ignored": false, "parentId": "2", "role": { "type": "role", "value": "checkbox" },
+          "name": { "type": "computedString", "value": "Remember me" }, "value": { "type": "tristate", "value": "true" } },
+    ]))
+    .unwrap();
+
+    let tree = stagehand_sdk::accessibility::from_cdp_nodes(&nodes).unwrap();
+    assert_eq!(tree.backend_node_id, Some(10));
+    // The ignored generic container is dropped and its children lifted
+    assert_eq!(
+        tree.to_outline(),
+        "RootWebArea \"Example\"\n  heading \"Example Domain\"\n  checkbox \"Remember me\" = \"true\"\n",
+    );
+}
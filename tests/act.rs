@@ -0,0 +1,94 @@
+use stagehand_sdk::{ActInput, ObservedAction};
+
+mod common;
+
+#[test]
+fn test_act_input_wire_format() {
+    let instruction = ActInput::from("Click the login button");
+    assert_eq!(serde_json::to_value(&instruction).unwrap(), "Click the login button");
+
+    // An observed action is sent back in the shape `observe` returned it
+    let action = ActInput::from(ObservedAction {
+        selector: "xpath=/html/body/form/input[2]".to_string(),
+        description: "Password field".to_string(),
+        backend_node_id: Some(42),
+        method: Some("fill".to_string()),
+        arguments: vec!["%password%".to_string()],
+    });
+    assert_eq!(
+        serde_json::to_value(&action).unwrap(),
+        serde_json::json!({
+            "selector": "xpath=/html/body/form/input[2]",
+            "description": "Password field",
+            "backendNodeId": 42,
+            "method": "fill",
+            "arguments": ["%password%"],
+        }),
+    );
+
+    let minimal = ActInput::Action(ObservedAction {
+        selector: "#submit".to_string(),
+        description: "Submit".to_string(),
+        backend_node_id: None,
+        method: None,
+        arguments: vec![],
+    });
+    assert_eq!(serde_json::to_value(&minimal).unwrap(), serde_json::json!({ "selector": "#submit", "description": "Submit" }));
+}
+
+fn sse(events: &[serde_json::Value]) -> String {
+    events.iter().map(|event| format!("data: {}\n\n", event)).collect()
+}
+
+fn finished(result: serde_json::Value) -> String {
+    sse(&[serde_json::json!({ "type": "system", "data": { "status": "finished", "result": result } })])
+}
+
+#[tokio::test]
+async fn test_cached_act_falls_back_to_inference_when_replay_fails() {
+    use futures::StreamExt;
+    use stagehand_sdk::{ActOptions, ActResponseEvent, ActionCache, ActionCacheKey, InMemoryActionCache, NavigateOptions, V3Options};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+
+    let stale = ObservedAction {
+        selector: "#old-login".to_string(),
+        description: "Login button".to_string(),
+        backend_node_id: None,
+        method: Some("click".to_string()),
+        arguments: vec![],
+    };
+    let fresh = ObservedAction { selector: "#login".to_string(), ..stale.clone() };
+    let replay = server.mock("POST", "/sessions/s1/act")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "input": { "selector": "#old-login" } })))
+        .with_status(500)
+        .create_async().await;
+    let inference = server.mock("POST", "/sessions/s1/act")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "input": "Click login" })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [serde_json::to_value(&fresh).unwrap()] })))
+        .create_async().await;
+
+    let cache = Arc::new(InMemoryActionCache::new());
+    let key = ActionCacheKey::new("https://example.com/login", "Click login", &HashMap::new(), None);
+    cache.put(&key, vec![stale]).unwrap();
+
+    let mut stagehand = common::connect(&server).await;
+    stagehand.set_action_cache(cache.clone());
+    stagehand.start(V3Options::default()).await.unwrap();
+    stagehand.navigate("https://example.com/login", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+    let events: Vec<_> = stagehand.act("Click login", ActOptions::default()).await.unwrap().collect().await;
+    let events: Vec<_> = events.into_iter().map(|event| event.unwrap().event).collect();
+    assert!(events.iter().any(|event| matches!(event, Some(ActResponseEvent::Success(true)))), "{:?}", events);
+
+    replay.assert_async().await;
+    inference.assert_async().await;
+    assert_eq!(cache.get(&key).unwrap(), Some(vec![fresh]));
+}
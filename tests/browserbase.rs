@@ -0,0 +1,287 @@
+use mockito::Matcher;
+use stagehand_sdk::{BrowserbaseClient, SESSION_UPLOADS_DIR, SessionStatus, SessionUpdateStatus, StagehandError, V3Options};
+use stagehand_sdk::{BrowserSettings, BrowserbaseSessionCreateParams, Fingerprint, FingerprintBrowser, ProxyConfig, Proxies, Region, Viewport};
+
+mod common;
+
+fn session_json(id: &str, status: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "projectId": "proj",
+        "status": status,
+        "createdAt": "2026-01-01T00:00:00Z",
+        "keepAlive": false,
+        "region": "us-west-2",
+        "connectUrl": "wss://connect.browserbase.com?signingKey=abc",
+    })
+}
+
+#[tokio::test]
+async fn test_create_and_get_session() {
+    let mut server = mockito::Server::new_async().await;
+    let create = server.mock("POST", "/sessions")
+        .match_header("x-bb-api-key", "key")
+        .match_body(Matcher::Json(serde_json::json!({ "projectId": "proj", "keepAlive": true })))
+        .with_status(201)
+        .with_body(session_json("s1", "RUNNING").to_string())
+        .create_async().await;
+    let get = server.mock("GET", "/sessions/s1")
+        .with_body(session_json("s1", "RUNNING").to_string())
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let params = BrowserbaseSessionCreateParams { keep_alive: Some(true), ..Default::default() };
+    let created = client.create_session(&params).await.unwrap();
+    assert_eq!(created.id, "s1");
+    assert_eq!(created.status, SessionStatus::Running);
+
+    // The connect URL gets a path before the query
+    let connect_url = client.session_connect_url("s1").await.unwrap();
+    assert_eq!(connect_url, "wss://connect.browserbase.com/?signingKey=abc");
+
+    create.assert_async().await;
+    get.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_list_and_release_sessions() {
+    let mut server = mockito::Server::new_async().await;
+    let list = server.mock("GET", "/sessions")
+        .match_query(Matcher::UrlEncoded("status".into(), "RUNNING".into()))
+        .with_body(serde_json::json!([session_json("s1", "RUNNING"), session_json("s2", "RUNNING")]).to_string())
+        .create_async().await;
+    let release = server.mock("POST", "/sessions/s1")
+        .match_body(Matcher::Json(serde_json::json!({ "projectId": "proj", "status": "REQUEST_RELEASE" })))
+        .with_body(session_json("s1", "COMPLETED").to_string())
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let sessions = client.list_sessions(Some(SessionStatus::Running)).await.unwrap();
+    assert_eq!(sessions.len(), 2);
+
+    let released = client.update_session("s1", SessionUpdateStatus::RequestRelease).await.unwrap();
+    assert_eq!(released.status, SessionStatus::Completed);
+
+    list.assert_async().await;
+    release.assert_async().await;
+}
+
+#[test]
+fn test_unknown_session_status() {
+    let status: SessionStatus = serde_json::from_value(serde_json::json!("PAUSED")).unwrap();
+    assert_eq!(status, SessionStatus::Unknown);
+}
+
+#[test]
+fn test_proxy_debug_hides_password() {
+    let proxy = ProxyConfig::External {
+        server: "http://proxy.example.com:8080".to_string(),
+        username: Some("scraper".to_string()),
+        password: Some("pr0xy-pass".to_string()),
+        domain_pattern: None,
+    };
+    let debug = format!("{:?}", proxy);
+    assert!(!debug.contains("pr0xy-pass"), "{}", debug);
+    assert!(debug.contains("scraper"), "{}", debug);
+}
+
+#[test]
+fn test_client_debug_hides_api_key() {
+    let client = BrowserbaseClient::new("bb_live_secret", "proj");
+    let debug = format!("{:?}", client);
+    assert!(!debug.contains("bb_live_secret"), "{}", debug);
+    assert!(debug.contains("proj"), "{}", debug);
+}
+
+#[tokio::test]
+async fn test_live_urls_logs_and_errors() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/sessions/s1/debug")
+        .with_body(serde_json::json!({
+            "debuggerFullscreenUrl": "https://live/full",
+            "debuggerUrl": "https://live",
+            "wsUrl": "wss://live",
+            "pages": [{
+                "id": "p1",
+                "url": "https://example.com",
+                "title": "Example",
+                "faviconUrl": null,
+                "debuggerUrl": "https://live/p1",
+                "debuggerFullscreenUrl": "https://live/p1/full",
+            }],
+        }).to_string())
+        .create_async().await;
+    server.mock("GET", "/sessions/s1/logs")
+        .with_body(serde_json::json!([
+            { "method": "Page.navigate", "pageId": 0, "sessionId": "s1", "timestamp": 1 },
+        ]).to_string())
+        .create_async().await;
+    server.mock("GET", "/sessions/missing")
+        .with_status(404)
+        .with_body("not found")
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let live = client.session_live_urls("s1").await.unwrap();
+    assert_eq!(live.pages[0].url, "https://example.com");
+
+    let logs = client.session_logs("s1").await.unwrap();
+    assert_eq!(logs[0].method, "Page.navigate");
+
+    let err = client.get_session("missing").await.unwrap_err();
+    assert!(matches!(err, StagehandError::Api(ref msg) if msg.contains("404")));
+}
+
+#[test]
+fn test_session_create_params_round_trip() {
+    let json = serde_json::json!({
+        "browserSettings": {
+            "fingerprint": { "browsers": ["chrome", "firefox"], "httpVersion": 2 },
+            "viewport": { "width": 1280, "height": 720 },
+            "blockAds": true,
+            "solveCaptchas": false,
+            "recordSession": true,
+            "context": { "id": "ctx", "persist": true },
+            "os": "linux",
+        },
+        "proxies": [
+            { "type": "browserbase", "geolocation": { "country": "US", "city": "NEW_YORK" } },
+            { "type": "external", "server": "http://proxy:8080", "domainPattern": ".*\\.example\\.com" },
+        ],
+        "region": "eu-central-1",
+        "keepAlive": true,
+        "timeout": 600,
+        "userMetadata": { "team": "ops" },
+        "futureField": { "enabled": true },
+    });
+
+    let params: BrowserbaseSessionCreateParams = serde_json::from_value(json.clone()).unwrap();
+    let settings = params.browser_settings.as_ref().unwrap();
+    assert_eq!(settings.viewport, Some(Viewport { width: 1280, height: 720 }));
+    assert_eq!(settings.fingerprint.as_ref().unwrap().browsers, vec![FingerprintBrowser::Chrome, FingerprintBrowser::Firefox]);
+    assert_eq!(settings.extra["os"], "linux");
+    assert!(matches!(params.proxies, Some(Proxies::Custom(ref rules)) if matches!(rules[1], ProxyConfig::External { .. })));
+    assert_eq!(params.region, Some(Region::EuCentral1));
+    assert_eq!(params.extra["futureField"], serde_json::json!({ "enabled": true }));
+
+    // Unknown fields survive the round trip
+    assert_eq!(serde_json::to_value(&params).unwrap(), json);
+
+    let minimal = BrowserbaseSessionCreateParams {
+        browser_settings: Some(BrowserSettings {
+            fingerprint: Some(Fingerprint::default()),
+            ..Default::default()
+        }),
+        proxies: Some(Proxies::Enabled(true)),
+        region: Some(Region::Other("sa-east-1".into())),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&minimal).unwrap(),
+        serde_json::json!({ "browserSettings": { "fingerprint": {} }, "proxies": true, "region": "sa-east-1" }),
+    );
+}
+
+#[tokio::test]
+async fn test_contexts() {
+    let mut server = mockito::Server::new_async().await;
+    let create = server.mock("POST", "/contexts")
+        .match_body(Matcher::Json(serde_json::json!({ "projectId": "proj" })))
+        .with_body(serde_json::json!({
+            "id": "ctx",
+            "uploadUrl": "https://upload",
+            "publicKey": "pk",
+            "cipherAlgorithm": "AES-256-CBC",
+            "initializationVectorSize": 16,
+        }).to_string())
+        .create_async().await;
+    let delete = server.mock("DELETE", "/contexts/ctx")
+        .with_status(204)
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let context = client.create_context().await.unwrap();
+    assert_eq!(context.id, "ctx");
+    client.delete_context(&context.id).await.unwrap();
+
+    create.assert_async().await;
+    delete.assert_async().await;
+}
+
+#[test]
+fn test_v3_options_with_browserbase_context() {
+    let opts = V3Options {
+        browserbase_session_create_params: Some(BrowserbaseSessionCreateParams { keep_alive: Some(true), ..Default::default() }),
+        ..Default::default()
+    }
+    .with_browserbase_context("ctx", true);
+
+    let params = opts.browserbase_session_create_params.unwrap();
+    assert_eq!(params.keep_alive, Some(true));
+    assert_eq!(
+        serde_json::to_value(&params.browser_settings).unwrap(),
+        serde_json::json!({ "context": { "id": "ctx", "persist": true } }),
+    );
+}
+
+#[tokio::test]
+async fn test_session_downloads() {
+    let mut server = mockito::Server::new_async().await;
+    let archive = common::zip_archive(&[("exports/", b""), ("exports/report.csv", b"a,b\n1,2\n"), ("../escape.pdf", b"%PDF"), ("blob", b"\x00\x01")]);
+    server.mock("GET", "/sessions/s1/downloads")
+        .with_header("content-type", "application/zip")
+        .with_body(archive)
+        .create_async().await;
+    server.mock("GET", "/sessions/s2/downloads")
+        .with_body("")
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let files = client.session_downloads("s1").await.unwrap();
+    let summary: Vec<_> = files.iter().map(|f| (f.name.as_str(), f.mime.as_str())).collect();
+    // Entries with a path outside the archive root are dropped
+    assert_eq!(summary, vec![("exports/report.csv", "text/csv"), ("blob", "application/octet-stream")]);
+    assert_eq!(files[0].bytes, b"a,b\n1,2\n");
+
+    assert!(client.session_downloads("s2").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_session_downloads_limits_unpacked_size() {
+    let mut server = mockito::Server::new_async().await;
+    // Each file is within the limit on its own, together they are over it
+    let zeros = vec![0u8; 65 * 1024 * 1024];
+    server.mock("GET", "/sessions/s1/downloads")
+        .with_header("content-type", "application/zip")
+        .with_body(common::zip_archive(&[("a.bin", &zeros), ("b.bin", &zeros)]))
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let err = client.session_downloads("s1").await.unwrap_err();
+    assert!(err.to_string().contains("exceed 128 MiB"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_upload_file() {
+    let path = std::env::temp_dir().join(format!("stagehand-upload-{}.csv", std::process::id()));
+    std::fs::write(&path, "name\nalice\n").unwrap();
+    let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+    let mut server = mockito::Server::new_async().await;
+    let upload = server.mock("POST", "/sessions/s1/uploads")
+        .match_header("content-type", Matcher::Regex("^multipart/form-data".into()))
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(format!(r#"name="file"; filename="{}""#, file_name)),
+            Matcher::Regex("Content-Type: text/csv".into()),
+            Matcher::Regex("name\r?\nalice".into()),
+        ]))
+        .with_body(r#"{"message":"File uploaded"}"#)
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let remote_path = client.upload_file("s1", &path).await.unwrap();
+    assert_eq!(remote_path, format!("{}/{}", SESSION_UPLOADS_DIR, file_name));
+    upload.assert_async().await;
+
+    std::fs::remove_file(&path).unwrap();
+}
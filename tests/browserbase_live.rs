@@ -1,8 +1,8 @@
 use stagehand_sdk::{Stagehand, V3Options, Env, Model, TransportChoice, AgentConfig, AgentExecuteOptions, ModelConfiguration};
 use stagehand_sdk::{ActResponseEvent, ExtractResponseEvent, ExecuteResponseEvent, NavigateResponseEvent, ObserveResponseEvent};
+use stagehand_sdk::{ActOptions, ExtractOptions, NavigateOptions, ObserveOptions};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug)]
 struct PageInfo {
@@ -33,7 +33,7 @@ async fn test_browserbase_live() -> Result<(), Box<dyn std::error::Error + Send
 
     // 4. Navigate to example.com
     println!("\n=== NAVIGATE ===");
-    let mut nav_stream = stagehand.navigate("https://example.com", Some(30_000), None).await?;
+    let mut nav_stream = stagehand.navigate("https://example.com", NavigateOptions::new().timeout(30_000)).await?;
 
     while let Some(msg) = nav_stream.next().await {
         if let Ok(event) = msg {
@@ -50,11 +50,10 @@ async fn test_browserbase_live() -> Result<(), Box<dyn std::error::Error + Send
     // 5. Observe - find elements on the page
     println!("\n=== OBSERVE ===");
     let mut observe_stream = stagehand.observe(
-        Some("Find the main heading and any links on the page".to_string()),
-        Some(Model::String("openai/gpt-5-nano".into())),
-        Some(30_000),
-        None,
-        None,
+        ObserveOptions::new()
+            .instruction("Find the main heading and any links on the page")
+            .model(Model::String("openai/gpt-5-nano".into()))
+            .timeout(30_000),
     ).await?;
 
     while let Some(msg) = observe_stream.next().await {
@@ -85,10 +84,9 @@ async fn test_browserbase_live() -> Result<(), Box<dyn std::error::Error + Send
     let mut extract_stream = stagehand.extract(
         "Extract the page title and description text",
         schema,
-        Some(Model::String("openai/gpt-5-nano".into())),
-        Some(30_000),
-        None,
-        None,
+        ExtractOptions::new()
+            .model(Model::String("openai/gpt-5-nano".into()))
+            .timeout(30_000),
     ).await?;
 
     while let Some(msg) = extract_stream.next().await {
@@ -116,10 +114,9 @@ async fn test_browserbase_live() -> Result<(), Box<dyn std::error::Error + Send
     println!("\n=== ACT ===");
     let mut act_stream = stagehand.act(
         "Click on the 'More information...' link",
-        Some(Model::String("openai/gpt-5-nano".into())),
-        HashMap::new(),
-        Some(30_000),
-        None,
+        ActOptions::new()
+            .model(Model::String("openai/gpt-5-nano".into()))
+            .timeout(30_000),
     ).await?;
 
     while let Some(msg) = act_stream.next().await {
@@ -0,0 +1,332 @@
+use futures::StreamExt;
+use mockito::Matcher;
+use stagehand_sdk::{
+    ActOptions, ActResponse, ActResponseEvent, ActionCache, ActionCacheKey, ExtractCache, ExtractCacheKey,
+    ExtractCacheStats, ExtractOptions, ExtractResponseEvent, InMemoryActionCache, JsonFileActionCache,
+    NavigateOptions, ObservedAction, SecretString, UploadTarget, V3Options, VariableValue,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn finished(result: serde_json::Value) -> String {
+    format!("data: {}\n\n", serde_json::json!({ "type": "system", "data": { "status": "finished", "result": result } }))
+}
+
+fn click_action() -> ObservedAction {
+    ObservedAction {
+        selector: "xpath=/html/body/div/p[2]/a".to_string(),
+        description: "More information link".to_string(),
+        backend_node_id: None,
+        method: Some("click".to_string()),
+        arguments: vec![],
+    }
+}
+
+#[test]
+fn test_key_ignores_query_and_fragment() {
+    let vars = HashMap::new();
+    let a = ActionCacheKey::new("https://example.com/login?next=/home#top", "Click login", &vars, None);
+    let b = ActionCacheKey::new("https://example.com/login", "Click login", &vars, None);
+    assert_eq!(a, b);
+
+    let other_page = ActionCacheKey::new("https://example.com/signup", "Click login", &vars, None);
+    assert_ne!(a, other_page);
+}
+
+#[test]
+fn test_key_depends_on_frame_url() {
+    let vars = HashMap::new();
+    let main = ActionCacheKey::new("https://example.com/checkout", "Click pay", &vars, None);
+    let card = ActionCacheKey::new("https://example.com/checkout", "Click pay", &vars, Some("https://js.stripe.com/v3/card.html?id=run1#origin"));
+    assert_ne!(main, card);
+    assert!(!serde_json::to_string(&main).unwrap().contains("frame"));
+
+    // A later run loads the same iframe with another query, and keys it the same
+    let next_run = ActionCacheKey::new("https://example.com/checkout", "Click pay", &vars, Some("https://js.stripe.com/v3/card.html?id=run2"));
+    assert_eq!(card, next_run);
+    assert_eq!(card.frame.as_deref(), Some("https://js.stripe.com/v3/card.html"));
+}
+
+#[test]
+fn test_key_depends_on_plain_variables_but_not_secrets() {
+    let mut vars = HashMap::new();
+    vars.insert("password".to_string(), SecretString::new("hunter2").into());
+    let a = ActionCacheKey::new("https://example.com", "Type %password%", &vars, None);
+
+    vars.insert("password".to_string(), SecretString::new("correct horse").into());
+    let b = ActionCacheKey::new("https://example.com", "Type %password%", &vars, None);
+    assert_eq!(a, b);
+
+    vars.insert("user".to_string(), VariableValue::from("alice"));
+    let alice = ActionCacheKey::new("https://example.com", "Type %password%", &vars, None);
+    vars.insert("user".to_string(), VariableValue::from("bob"));
+    let bob = ActionCacheKey::new("https://example.com", "Type %password%", &vars, None);
+    assert_ne!(a, alice);
+    assert_ne!(alice, bob);
+}
+
+#[test]
+fn test_in_memory_cache() {
+    let cache = InMemoryActionCache::new();
+    let key = ActionCacheKey::new("https://example.com", "Click the link", &HashMap::new(), None);
+
+    assert_eq!(cache.get(&key).unwrap(), None);
+    cache.put(&key, vec![click_action()]).unwrap();
+    assert_eq!(cache.get(&key).unwrap(), Some(vec![click_action()]));
+    cache.remove(&key).unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_json_file_cache_persists() {
+    let path = std::env::temp_dir().join(format!("stagehand-action-cache-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let key = ActionCacheKey::new("https://example.com", "Click the link", &HashMap::new(), None);
+    let iframe_key = |frame_url| ActionCacheKey::new("https://example.com", "Click the link", &HashMap::new(), Some(frame_url));
+
+    {
+        let cache = JsonFileActionCache::open(&path).unwrap();
+        cache.put(&key, vec![click_action()]).unwrap();
+        cache.put(&iframe_key("https://widgets.example.com/embed?session=1"), vec![click_action()]).unwrap();
+    }
+
+    let reopened = JsonFileActionCache::open(&path).unwrap();
+    assert_eq!(reopened.get(&key).unwrap(), Some(vec![click_action()]));
+    assert_eq!(reopened.get(&iframe_key("https://widgets.example.com/embed?session=2")).unwrap(), Some(vec![click_action()]));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_act_replays_from_reopened_json_cache() {
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+    let action = serde_json::to_value(click_action()).unwrap();
+    let inference = server.mock("POST", "/sessions/s1/act")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "input": "Click the link" })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [action.clone()] })))
+        .expect(1)
+        .create_async().await;
+    let replay = server.mock("POST", "/sessions/s1/act")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "input": action.clone() })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [action] })))
+        .expect(1)
+        .create_async().await;
+
+    let path = std::env::temp_dir().join(format!("stagehand-action-replay-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    // The first run infers the action and writes it to the file, the second replays it
+    for _ in 0..2 {
+        let mut stagehand = common::connect(&server).await;
+        stagehand.set_action_cache(Arc::new(JsonFileActionCache::open(&path).unwrap()));
+        stagehand.start(V3Options::default()).await.unwrap();
+        stagehand.navigate("https://example.com/", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+        let events: Vec<_> = stagehand.act("Click the link", ActOptions::default()).await.unwrap().collect().await;
+        assert!(events.iter().any(|event| matches!(event, Ok(ActResponse { event: Some(ActResponseEvent::Success(true)) }))), "{:?}", events);
+    }
+
+    inference.assert_async().await;
+    replay.assert_async().await;
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_act_succeeds_when_cache_write_fails() {
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+    server.mock("POST", "/sessions/s1/act")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [click_action()] })))
+        .expect(1)
+        .create_async().await;
+
+    // The cache file's directory doesn't exist, so every write fails
+    let path = std::env::temp_dir().join(format!("stagehand-missing-{}", std::process::id())).join("actions.json");
+    let mut stagehand = common::connect(&server).await;
+    stagehand.set_action_cache(Arc::new(JsonFileActionCache::open(&path).unwrap()));
+    stagehand.start(V3Options::default()).await.unwrap();
+    stagehand.navigate("https://example.com/", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+    let events: Vec<_> = stagehand.act("Click the link", ActOptions::default()).await.unwrap().collect().await;
+    // The act the server performed still succeeds, with the failed write in a log line
+    assert!(events.iter().all(Result::is_ok), "{:?}", events);
+    assert!(events.iter().any(|event| matches!(event, Ok(ActResponse { event: Some(ActResponseEvent::Success(true)) }))), "{:?}", events);
+    assert!(events.iter().any(|event| matches!(event, Ok(ActResponse { event: Some(ActResponseEvent::Log(log)) }) if log.message.contains("not cached"))), "{:?}", events);
+}
+
+#[tokio::test]
+async fn test_upload_file_invalidates_cached_page_url() {
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+    server.mock("POST", "/sessions/s1/uploads")
+        .with_body(r#"{"message":"File uploaded"}"#)
+        .create_async().await;
+    server.mock("POST", "/sessions/s1/act")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [click_action()] })))
+        .expect(2)
+        .create_async().await;
+
+    let path = std::env::temp_dir().join(format!("stagehand-cache-upload-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello").unwrap();
+    let cache = Arc::new(InMemoryActionCache::new());
+    let mut stagehand = common::connect(&server).await;
+    stagehand.set_action_cache(cache.clone());
+    stagehand.start(V3Options::default()).await.unwrap();
+    stagehand.navigate("https://example.com/", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+    let events: Vec<_> = stagehand.upload_file(UploadTarget::Selector("#file".to_string()), &path).await.unwrap().collect().await;
+    assert!(events.iter().all(Result::is_ok), "{:?}", events);
+
+    // Setting the file may have navigated, so the next act isn't keyed by the old URL
+    stagehand.act("Click the link", ActOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+    assert!(cache.is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+fn title_key(dom_hash: &str) -> ExtractCacheKey {
+    let schema = serde_json::json!({ "type": "object", "properties": { "title": { "type": "string" } } });
+    ExtractCacheKey::new("https://example.com/#main", "Extract the title", &schema, None, Some(dom_hash.to_string()), None)
+}
+
+#[test]
+fn test_extract_cache_hits_and_misses() {
+    let cache = ExtractCache::new(Duration::from_secs(60));
+
+    assert_eq!(cache.get(&title_key("a")), None);
+    cache.put(title_key("a"), r#"{"title":"Example Domain"}"#.to_string()).unwrap();
+    assert_eq!(cache.get(&title_key("a")).as_deref(), Some(r#"{"title":"Example Domain"}"#));
+
+    // Changed page content misses
+    assert_eq!(cache.get(&title_key("b")), None);
+    assert_eq!(cache.stats(), ExtractCacheStats { hits: 1, misses: 2 });
+}
+
+#[test]
+fn test_extract_cache_expires_entries() {
+    let cache = ExtractCache::new(Duration::ZERO);
+    cache.put(title_key("a"), "{}".to_string()).unwrap();
+    assert_eq!(cache.get(&title_key("a")), None);
+}
+
+#[test]
+fn test_extract_cache_ttl_below_one_second() {
+    let cache = ExtractCache::new(Duration::from_millis(300));
+    cache.put(title_key("a"), "{}".to_string()).unwrap();
+    assert_eq!(cache.get(&title_key("a")).as_deref(), Some("{}"));
+
+    std::thread::sleep(Duration::from_millis(400));
+    assert_eq!(cache.get(&title_key("a")), None);
+}
+
+#[tokio::test]
+async fn test_extract_cache_checks_page_content() {
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+    // Without instruction or schema, extract returns the page text and runs no inference
+    let content = server.mock("POST", "/sessions/s1/extract")
+        .match_body(Matcher::Json(serde_json::json!({})))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "pageText": "Example Domain" })))
+        .expect(2)
+        .create_async().await;
+    let inference = server.mock("POST", "/sessions/s1/extract")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "instruction": "Extract the title" })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "title": "Example Domain" })))
+        .expect(1)
+        .create_async().await;
+
+    let cache = Arc::new(ExtractCache::new(Duration::from_secs(60)));
+    let mut stagehand = common::connect(&server).await;
+    stagehand.set_extract_cache(cache.clone());
+    stagehand.start(V3Options::default()).await.unwrap();
+    stagehand.navigate("https://example.com/", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+    // The second extract sees the same page content and is served from the cache
+    let schema = serde_json::json!({ "type": "object", "properties": { "title": { "type": "string" } } });
+    for _ in 0..2 {
+        let events: Vec<_> = stagehand.extract("Extract the title", schema.clone(), ExtractOptions::default()).await.unwrap().collect().await;
+        let data: Vec<_> = events.into_iter().filter_map(|event| match event.unwrap().event {
+            Some(ExtractResponseEvent::DataJson(json)) => Some(json),
+            _ => None,
+        }).collect();
+        assert_eq!(data, vec![r#"{"title":"Example Domain"}"#.to_string()]);
+    }
+
+    content.assert_async().await;
+    inference.assert_async().await;
+    assert_eq!(cache.stats(), ExtractCacheStats { hits: 1, misses: 1 });
+}
+
+#[tokio::test]
+async fn test_extract_succeeds_when_cache_write_fails() {
+    let mut server = common::mock_api().await;
+    server.mock("POST", "/sessions/s1/navigate")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!(null)))
+        .create_async().await;
+    server.mock("POST", "/sessions/s1/extract")
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "title": "Example Domain" })))
+        .create_async().await;
+
+    // The cache file's directory doesn't exist, so every write fails
+    let path = std::env::temp_dir().join(format!("stagehand-missing-{}", std::process::id())).join("extract.json");
+    let cache = ExtractCache::open(&path, Duration::from_secs(60)).unwrap().check_page_content(false);
+    let mut stagehand = common::connect(&server).await;
+    stagehand.set_extract_cache(Arc::new(cache));
+    stagehand.start(V3Options::default()).await.unwrap();
+    stagehand.navigate("https://example.com/", NavigateOptions::default()).await.unwrap().collect::<Vec<_>>().await;
+
+    let schema = serde_json::json!({ "type": "object", "properties": { "title": { "type": "string" } } });
+    let events: Vec<_> = stagehand.extract("Extract the title", schema, ExtractOptions::default()).await.unwrap()
+        .map(|event| event.unwrap().event)
+        .collect().await;
+    assert!(matches!(&events[..], [Some(ExtractResponseEvent::Log(log)), Some(ExtractResponseEvent::DataJson(json))]
+        if log.message.contains("not cached") && json == r#"{"title":"Example Domain"}"#), "{:?}", events);
+}
+
+#[test]
+fn test_extract_key_depends_on_frame() {
+    let schema = serde_json::json!({});
+    let main = ExtractCacheKey::new("https://example.com/", "Extract the title", &schema, None, None, None);
+    let frame = ExtractCacheKey::new("https://example.com/", "Extract the title", &schema, None, None, Some("https://widgets.example.com/reviews?page=1"));
+    assert_ne!(main, frame);
+    assert_eq!(frame.frame.as_deref(), Some("https://widgets.example.com/reviews"));
+}
+
+#[test]
+fn test_extract_cache_persists() {
+    let path = std::env::temp_dir().join(format!("stagehand-extract-cache-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    {
+        let cache = ExtractCache::open(&path, Duration::from_secs(60)).unwrap();
+        cache.put(title_key("a"), "{}".to_string()).unwrap();
+    }
+
+    let reopened = ExtractCache::open(&path, Duration::from_secs(60)).unwrap();
+    assert_eq!(reopened.get(&title_key("a")).as_deref(), Some("{}"));
+
+    std::fs::remove_file(&path).unwrap();
+}
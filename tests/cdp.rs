@@ -0,0 +1,39 @@
+#![cfg(feature = "chromiumoxide")]
+
+use stagehand_sdk::{CdpBrowser, StagehandError};
+
+mod common;
+
+#[tokio::test]
+async fn test_cdp_connect_failure_is_cdp_error() {
+    // Nothing listens on this port, so every attempt fails
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let err = CdpBrowser::connect(&format!("ws://127.0.0.1:{}/devtools/browser/x", port)).await.err().unwrap();
+    assert!(matches!(err, StagehandError::Cdp(_)), "unexpected error: {:?}", err);
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_active_page_follows_newest_open_page() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/first", "text/html", "<title>First</title>"), ("/second", "text/html", "<title>Second</title>")]);
+    let cdp = CdpBrowser::connect(browser.websocket_address()).await.unwrap();
+
+    let first = cdp.browser.new_page(format!("{}/first", site)).await.unwrap();
+    let second = cdp.browser.new_page(format!("{}/second", site)).await.unwrap();
+    // Target events arrive asynchronously
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(cdp.active_page().await.unwrap().target_id(), second.target_id());
+
+    second.close().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(cdp.active_page().await.unwrap().target_id(), first.target_id());
+
+    drop(cdp);
+    drop(browser);
+    handler_task.abort();
+}
@@ -10,9 +10,8 @@
 use chromiumoxide::browser::Browser;
 use chromiumoxide::cdp::browser_protocol::page::{GetFrameTreeParams, NavigateParams};
 use futures::StreamExt;
-use stagehand_sdk::{ActResponseEvent, ExtractResponseEvent};
+use stagehand_sdk::{ActOptions, ActResponseEvent, ExtractOptions, ExtractResponseEvent};
 use stagehand_sdk::{Env, Model, Stagehand, TransportChoice, V3Options};
-use std::collections::HashMap;
 
 /// Test that creates a Browserbase session via Stagehand and connects chromiumoxide to it
 #[tokio::test]
@@ -122,10 +121,7 @@ async fn test_chromiumoxide_browserbase_connection(
         .extract(
             "Extract the page title and current URL",
             schema,
-            None,
-            Some(30_000),
-            None,
-            Some(frame_id.clone()),
+            ExtractOptions::new().timeout(30_000).frame_id(frame_id.clone()),
         )
         .await?;
 
@@ -146,10 +142,7 @@ async fn test_chromiumoxide_browserbase_connection(
     let mut act_stream = stagehand
         .act(
             "Click on the 'More information...' link",
-            None,
-            HashMap::new(),
-            Some(30_000),
-            Some(frame_id.clone()),
+            ActOptions::new().timeout(30_000).frame_id(frame_id.clone()),
         )
         .await?;
 
@@ -0,0 +1,139 @@
+//! Helpers for tests against a mock API, and for tests that drive a locally installed Chrome
+
+#![allow(dead_code)]
+
+#[cfg(feature = "chromiumoxide")]
+use chromiumoxide::browser::{Browser, BrowserConfig};
+#[cfg(feature = "chromiumoxide")]
+use futures::StreamExt;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+#[cfg(feature = "chromiumoxide")]
+use tokio::task::JoinHandle;
+
+/// Serializes the tests of a binary that set the environment
+static ENVIRONMENT: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// A mock server from [`mock_api`], holding the environment until dropped
+pub struct MockApi {
+    server: mockito::ServerGuard,
+    _environment: tokio::sync::MutexGuard<'static, ()>,
+}
+
+impl std::ops::Deref for MockApi {
+    type Target = mockito::ServerGuard;
+
+    fn deref(&self) -> &Self::Target {
+        &self.server
+    }
+}
+
+impl std::ops::DerefMut for MockApi {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.server
+    }
+}
+
+/// A mock Stagehand and Browserbase API that answers `start` with session `s1`; add mocks for
+/// the calls a test makes.
+///
+/// Sets the API key environment variables and points `BROWSERBASE_API_URL` at the server. Other
+/// tests calling it wait until the returned server is dropped.
+pub async fn mock_api() -> MockApi {
+    let environment = ENVIRONMENT.lock().await;
+    let mut server = mockito::Server::new_async().await;
+    // SAFETY: the lock keeps other tests of this binary from touching the environment
+    unsafe {
+        std::env::set_var("MODEL_API_KEY", "model-key");
+        std::env::set_var("BROWSERBASE_API_KEY", "bb-key");
+        std::env::set_var("BROWSERBASE_PROJECT_ID", "project");
+        std::env::set_var("BROWSERBASE_API_URL", server.url());
+    }
+    server.mock("POST", "/sessions/start")
+        .with_body(serde_json::json!({ "success": true, "data": { "available": true, "sessionId": "s1" } }).to_string())
+        .create_async().await;
+    MockApi { server, _environment: environment }
+}
+
+/// A Stagehand client for `server` (see [`mock_api`]), not started yet
+pub async fn connect(server: &mockito::ServerGuard) -> stagehand_sdk::Stagehand {
+    stagehand_sdk::Stagehand::connect(stagehand_sdk::TransportChoice::Rest(server.url())).await.unwrap()
+}
+
+/// A zip archive of `files` (path, contents). Paths ending in `/` are added as directories.
+pub fn zip_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for (name, bytes) in files {
+        if name.ends_with('/') {
+            writer.add_directory(*name, options).unwrap();
+        } else {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// Launch a headless Chrome, panicking when none is installed or it fails to start
+#[cfg(feature = "chromiumoxide")]
+pub async fn launch_chrome() -> (Browser, JoinHandle<()>) {
+    let config = BrowserConfig::builder()
+        .arg("--no-sandbox")
+        .build()
+        .unwrap_or_else(|e| panic!("Chrome-backed tests need a local Chrome: {}", e));
+    let (browser, mut handler) = Browser::launch(config)
+        .await
+        .unwrap_or_else(|e| panic!("Chrome failed to launch: {}", e));
+    (browser, tokio::spawn(async move { while handler.next().await.is_some() {} }))
+}
+
+/// Serve `routes` (path, content type, body) over HTTP on a local port, returning the base URL.
+/// Unknown paths get the first route.
+pub fn serve(routes: &'static [(&'static str, &'static str, &'static str)]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap_or_default();
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+
+            let (_, content_type, body) = routes.iter().find(|(route, _, _)| *route == path).unwrap_or(&routes[0]);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                content_type, body.len(), body,
+            );
+            let _ = reader.into_inner().write_all(response.as_bytes());
+        }
+    });
+    url
+}
+
+/// A started Stagehand session whose API is a [`mock_api`] and whose browser is `browser`.
+///
+/// The mock server also points the session's `connectUrl` at `browser`.
+#[cfg(feature = "chromiumoxide")]
+pub async fn local_stagehand(browser: &Browser) -> (stagehand_sdk::Stagehand, MockApi) {
+    let mut server = mock_api().await;
+    server.mock("GET", "/sessions/s1")
+        .with_body(serde_json::json!({
+            "id": "s1",
+            "projectId": "project",
+            "status": "RUNNING",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "keepAlive": false,
+            "region": "us-west-2",
+            "connectUrl": browser.websocket_address(),
+        }).to_string())
+        .create_async().await;
+
+    let mut stagehand = connect(&server).await;
+    stagehand.start(stagehand_sdk::V3Options::default()).await.unwrap();
+    (stagehand, server)
+}
@@ -0,0 +1,24 @@
+use stagehand_sdk::V3Options;
+
+mod common;
+
+#[tokio::test]
+async fn test_downloads_to_dir_keeps_archive_paths() {
+    let mut server = common::mock_api().await;
+    server.mock("GET", "/sessions/s1/downloads")
+        .with_body(common::zip_archive(&[("january/report.csv", b"jan"), ("february/report.csv", b"feb"), ("notes.txt", b"hi")]))
+        .create_async().await;
+
+    let mut stagehand = common::connect(&server).await;
+    stagehand.start(V3Options::default()).await.unwrap();
+
+    let dir = std::env::temp_dir().join(format!("stagehand-downloads-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let paths = stagehand.downloads_to_dir(&dir).await.unwrap();
+
+    assert_eq!(paths, vec![dir.join("january/report.csv"), dir.join("february/report.csv"), dir.join("notes.txt")]);
+    assert_eq!(std::fs::read(dir.join("january/report.csv")).unwrap(), b"jan");
+    assert_eq!(std::fs::read(dir.join("february/report.csv")).unwrap(), b"feb");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
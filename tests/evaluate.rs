@@ -0,0 +1,47 @@
+#![cfg(feature = "chromiumoxide")]
+
+use serde_json::json;
+use stagehand_sdk::page::evaluate_params;
+
+mod common;
+
+#[test]
+fn test_evaluate_params_pass_one_argument_per_value() {
+    let params = evaluate_params("(ids, missing) => ids.length", &[json!(["a", "b"]), json!(null)]);
+    assert_eq!(
+        params.expression,
+        "(async () => { const value = ((ids, missing) => ids.length\n); \
+         return typeof value === 'function' ? await value(...[[\"a\",\"b\"],null]) : await value; })()",
+    );
+    assert_eq!(params.await_promise, Some(true));
+    assert_eq!(params.return_by_value, Some(true));
+    assert_eq!(params.context_id, None);
+
+    let params = evaluate_params("document.title // the tab's title", &[]);
+    assert!(params.expression.contains("(document.title // the tab's title\n)"), "{}", params.expression);
+    assert!(params.expression.contains("value(...[])"), "{}", params.expression);
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_evaluate_maps_arguments_against_local_chrome() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><head><title>Cart</title></head></html>")]);
+    let (mut stagehand, _server) = common::local_stagehand(&browser).await;
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+
+    let title: String = page.evaluate("document.title", &[]).await.unwrap();
+    assert_eq!(title, "Cart");
+    let sum: f64 = page.evaluate("async (a, b) => a + b", &[json!(1.5), json!(2)]).await.unwrap();
+    assert_eq!(sum, 3.5);
+    // Arrays and null are single arguments, not spread or dropped
+    let args: serde_json::Value = page.evaluate("(...args) => args", &[json!([1, 2]), json!(null)]).await.unwrap();
+    assert_eq!(args, json!([[1, 2], null]));
+    let count: usize = page.evaluate("(...args) => args.length", &[]).await.unwrap();
+    assert_eq!(count, 0);
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}
@@ -0,0 +1,79 @@
+use stagehand_sdk::FrameInfo;
+
+fn frame(id: &str, url: &str, parent_id: Option<&str>, children: Vec<FrameInfo>) -> FrameInfo {
+    FrameInfo {
+        id: id.into(),
+        url: url.into(),
+        name: None,
+        parent_id: parent_id.map(Into::into),
+        children,
+    }
+}
+
+fn checkout_page() -> FrameInfo {
+    let mut widget = frame("widget", "https://widgets.example.com/chat?x=1", Some("main"), vec![]);
+    widget.name = Some("chat".into());
+    frame("main", "https://shop.example.com/checkout", None, vec![
+        frame("ads", "about:blank", Some("main"), vec![
+            frame("card", "https://js.stripe.com/v3/elements-inner-card.html#id=1", Some("ads"), vec![]),
+        ]),
+        widget,
+    ])
+}
+
+#[test]
+fn test_frame_tree_iteration_and_lookup() {
+    let page = checkout_page();
+    let ids: Vec<_> = page.iter().map(|f| f.id.as_str()).collect();
+    assert_eq!(ids, vec!["main", "ads", "card", "widget"]);
+
+    assert_eq!(page.find_by_url("https://js.stripe.com/*").unwrap().id, "card");
+    assert_eq!(page.find_by_url("*widgets.example.com/ch?t*").unwrap().id, "widget");
+    assert_eq!(page.find_by_url("*checkout").unwrap().id, "main");
+    assert_eq!(page.find_by_name("chat").unwrap().id, "widget");
+
+    // Patterns match the whole URL
+    assert!(page.find_by_url("js.stripe.com").is_none());
+    assert!(page.find_by_url("https://shop.example.com/").is_none());
+    assert!(page.find_by_url("*paypal*").is_none());
+}
+
+#[cfg(feature = "chromiumoxide")]
+#[test]
+fn test_frame_info_from_cdp_frame_tree() {
+    use chromiumoxide::cdp::browser_protocol::page::FrameTree;
+
+    let cdp_frame = |id: &str, parent: Option<&str>, name: &str, url: &str| {
+        let mut frame = serde_json::json!({
+            "id": id,
+            "loaderId": "loader",
+            "name": name,
+            "url": url,
+            "domainAndRegistry": "example.com",
+            "securityOrigin": "https://example.com",
+            "mimeType": "text/html",
+            "secureContextType": "Secure",
+            "crossOriginIsolatedContextType": "NotIsolated",
+            "gatedAPIFeatures": [],
+        });
+        if let Some(parent) = parent {
+            frame["parentId"] = parent.into();
+        }
+        frame
+    };
+    let tree: FrameTree = serde_json::from_value(serde_json::json!({
+        "frame": cdp_frame("main", None, "", "https://example.com/"),
+        "childFrames": [{ "frame": cdp_frame("pay", Some("main"), "payment", "https://pay.example.com/form") }],
+    }))
+    .unwrap();
+
+    let info = FrameInfo::from(&tree);
+    assert_eq!(info.name, None);
+    assert_eq!(info.children[0], FrameInfo {
+        id: "pay".into(),
+        url: "https://pay.example.com/form".into(),
+        name: Some("payment".into()),
+        parent_id: Some("main".into()),
+        children: vec![],
+    });
+}
@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use stagehand_sdk::{BrowserbaseClient, HandoffOutcome, HandoffRequest, HumanHandoff, StagehandError};
+
+async fn live_view_server() -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/sessions/s1/debug")
+        .with_body(serde_json::json!({
+            "debuggerFullscreenUrl": "https://live/full",
+            "debuggerUrl": "https://live",
+            "wsUrl": "wss://live",
+            "pages": [],
+        }).to_string())
+        .create_async().await;
+    server
+}
+
+#[tokio::test]
+async fn test_handoff_completes_when_signalled() {
+    let server = live_view_server().await;
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+
+    let notified = Arc::new(Mutex::new(Vec::new()));
+    let log = notified.clone();
+    let handoff = HumanHandoff::new(move |request: HandoffRequest| {
+        log.lock().unwrap().push((request.reason.clone(), request.live_url().to_string()));
+        // The human finishes later, from another task
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            request.completion.complete();
+        });
+        Ok(())
+    })
+    .timeout(Duration::from_secs(5));
+
+    let outcome = handoff.run(&client, "s1", "Enter the 2FA code").await.unwrap();
+    assert_eq!(outcome, HandoffOutcome::Completed);
+    assert_eq!(*notified.lock().unwrap(), vec![("Enter the 2FA code".to_string(), "https://live/full".to_string())]);
+}
+
+#[tokio::test]
+async fn test_handoff_timeout_and_abort() {
+    let server = live_view_server().await;
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+
+    let pending = Arc::new(Mutex::new(Vec::new()));
+    let keep = pending.clone();
+    let handoff = HumanHandoff::new(move |request: HandoffRequest| {
+        // Keep the request alive without ever signalling
+        keep.lock().unwrap().push(request);
+        Ok(())
+    })
+    .timeout(Duration::from_millis(20));
+    assert_eq!(handoff.run(&client, "s1", "Solve the CAPTCHA").await.unwrap(), HandoffOutcome::TimedOut);
+
+    let declined = HumanHandoff::new(|request: HandoffRequest| {
+        request.completion.abort("Nobody on call");
+        Ok(())
+    });
+    assert_eq!(declined.run(&client, "s1", "Solve the CAPTCHA").await.unwrap(), HandoffOutcome::Aborted("Nobody on call".into()));
+
+    let failing = HumanHandoff::new(|_: HandoffRequest| Err(StagehandError::Api("Webhook down".into())));
+    assert!(failing.run(&client, "s1", "Solve the CAPTCHA").await.is_err());
+}
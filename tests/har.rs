@@ -0,0 +1,100 @@
+use stagehand_sdk::{Har, HarCache, HarContent, HarEntry, HarNameValue, HarRequest, HarResponse, HarTimings};
+
+#[cfg(feature = "chromiumoxide")]
+mod common;
+
+fn entry() -> HarEntry {
+    HarEntry {
+        started_date_time: "2026-01-01T00:00:00.000Z".into(),
+        time: 12.5,
+        request: HarRequest {
+            method: "GET".into(),
+            url: "https://example.com/?q=1".into(),
+            http_version: "HTTP/2".into(),
+            cookies: vec![],
+            headers: vec![HarNameValue { name: "Accept".into(), value: "*/*".into() }],
+            query_string: vec![HarNameValue { name: "q".into(), value: "1".into() }],
+            post_data: None,
+            headers_size: -1,
+            body_size: 0,
+        },
+        response: HarResponse {
+            status: 301,
+            status_text: "Moved Permanently".into(),
+            http_version: "HTTP/2".into(),
+            cookies: vec![],
+            headers: vec![],
+            content: HarContent { size: 0, mime_type: "text/html".into(), text: None, encoding: None },
+            redirect_url: "https://www.example.com/".into(),
+            headers_size: -1,
+            body_size: -1,
+        },
+        cache: HarCache::default(),
+        timings: HarTimings { blocked: -1.0, dns: -1.0, connect: -1.0, send: 0.5, wait: 10.0, receive: 2.0, ssl: -1.0 },
+        server_ip_address: Some("93.184.216.34".into()),
+        resource_type: Some("Document".into()),
+        error: None,
+    }
+}
+
+#[test]
+fn test_har_json_follows_spec() {
+    let har = Har::new(vec![entry()]);
+    let json = serde_json::to_value(&har).unwrap();
+
+    assert_eq!(json["log"]["version"], "1.2");
+    assert_eq!(json["log"]["creator"]["name"], "stagehand_sdk");
+    let entry = &json["log"]["entries"][0];
+    assert_eq!(entry["startedDateTime"], "2026-01-01T00:00:00.000Z");
+    assert_eq!(entry["serverIPAddress"], "93.184.216.34");
+    assert_eq!(entry["_resourceType"], "Document");
+    assert_eq!(entry["request"]["queryString"][0]["name"], "q");
+    assert_eq!(entry["response"]["redirectURL"], "https://www.example.com/");
+    assert_eq!(entry["cache"], serde_json::json!({}));
+    // Optional fields are left out rather than written as null
+    assert!(entry.get("_error").is_none());
+    assert!(entry["response"]["content"].get("text").is_none());
+}
+
+#[test]
+fn test_har_write_to() {
+    let path = std::env::temp_dir().join(format!("stagehand-{}.har", std::process::id()));
+    let har = Har::new(vec![entry()]);
+    har.write_to(&path).unwrap();
+
+    let read: Har = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(read, har);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_har_capture_against_local_chrome() {
+    use stagehand_sdk::{HarCapture, HarCaptureOptions};
+
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[
+        ("/", "text/html", "<html><body><script>window.fetched = fetch('/api/data?id=7').then(r => r.text())</script></body></html>"),
+        ("/api/data?id=7", "application/json", r#"{"id":7}"#),
+    ]);
+    let page = browser.new_page("about:blank").await.unwrap();
+    let capture = HarCapture::start(&page, HarCaptureOptions::new().content(true)).await.unwrap();
+
+    page.goto(format!("{}/", site)).await.unwrap();
+    page.evaluate("window.fetched").await.unwrap();
+    // Stopping right after the fetch still records its last events
+    let har = capture.stop().await;
+
+    let api = har.log.entries.iter().find(|e| e.request.url.ends_with("/api/data?id=7")).expect("fetch recorded");
+    assert_eq!(api.request.method, "GET");
+    assert_eq!(api.request.query_string, vec![HarNameValue { name: "id".into(), value: "7".into() }]);
+    assert_eq!(api.response.status, 200);
+    assert_eq!(api.response.content.mime_type, "application/json");
+    assert_eq!(api.response.content.text.as_deref(), Some(r#"{"id":7}"#));
+    assert!(har.log.entries[0].request.url.ends_with('/'), "entries are in request order");
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
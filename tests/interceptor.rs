@@ -0,0 +1,106 @@
+#![cfg(feature = "chromiumoxide")]
+
+mod common;
+
+use stagehand_sdk::{InterceptDecision, MockResponse, NetworkInterceptor, ResourceType};
+
+#[test]
+fn test_interceptor_rule_order() {
+    let interceptor = NetworkInterceptor::new()
+        .fulfill("*/api/prices*", MockResponse::json(&serde_json::json!({ "price": 10 })))
+        .block_resource_types([ResourceType::Image, ResourceType::Font])
+        .block_url("*://ads.example.com/*")
+        .rewrite_response("*/config.js", |_, body| body);
+
+    assert_eq!(interceptor.decide("https://shop.example.com/api/prices?id=1", &ResourceType::Fetch), InterceptDecision::Fulfill);
+    assert_eq!(interceptor.decide("https://shop.example.com/logo.png", &ResourceType::Image), InterceptDecision::Block);
+    assert_eq!(interceptor.decide("https://ads.example.com/pixel.js", &ResourceType::Script), InterceptDecision::Block);
+    assert_eq!(interceptor.decide("https://shop.example.com/config.js", &ResourceType::Script), InterceptDecision::RewriteResponse);
+    assert_eq!(interceptor.decide("https://shop.example.com/", &ResourceType::Document), InterceptDecision::Continue);
+
+    // The first matching rule wins, even if a later one would block
+    assert_eq!(interceptor.decide("https://shop.example.com/api/prices.png", &ResourceType::Image), InterceptDecision::Fulfill);
+}
+
+#[test]
+fn test_mock_response_json() {
+    let response = MockResponse::json(&serde_json::json!({ "ok": true })).header("X-Fixture", "1");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, br#"{"ok":true}"#);
+    assert_eq!(response.headers, vec![
+        ("Content-Type".to_string(), "application/json".to_string()),
+        ("X-Fixture".to_string(), "1".to_string()),
+    ]);
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_interceptor_against_local_chrome() {
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[
+        ("/", "text/html", "<html><head><title>Fixture</title></head><body>fixture</body></html>"),
+        ("/api/data", "application/json", r#"{"source":"network"}"#),
+        ("/config.json", "application/json", r#"{"env":"prod"}"#),
+    ]);
+    let page = browser.new_page("about:blank").await.unwrap();
+    let handle = NetworkInterceptor::new()
+        .fulfill("*/api/data", MockResponse::json(&serde_json::json!({ "source": "fixture" })))
+        .rewrite_response("*/config.json", |_, body| String::from_utf8_lossy(&body).replace("prod", "test").into())
+        .block_url("*/blocked.js")
+        .record_requests(true)
+        .attach(&page)
+        .await
+        .unwrap();
+
+    page.goto(format!("{}/", site)).await.unwrap();
+    let results: serde_json::Value = page
+        .evaluate(
+            "Promise.all([
+                fetch('/api/data').then(r => r.json()),
+                fetch('/config.json').then(r => r.json()),
+                fetch('/blocked.js').then(() => 'loaded', () => 'blocked'),
+            ])",
+        )
+        .await
+        .unwrap()
+        .into_value()
+        .unwrap();
+    assert_eq!(results, serde_json::json!([{ "source": "fixture" }, { "env": "test" }, "blocked"]));
+
+    let recorded = handle.detach().await.unwrap();
+    let decisions: Vec<_> = recorded.iter()
+        .filter(|r| r.url.starts_with(&site))
+        .map(|r| (r.url.trim_start_matches(&site), r.decision.clone()))
+        .collect();
+    assert_eq!(decisions, vec![
+        ("/", InterceptDecision::Continue),
+        ("/api/data", InterceptDecision::Fulfill),
+        ("/config.json", InterceptDecision::RewriteResponse),
+        ("/blocked.js", InterceptDecision::Block),
+    ]);
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_dropped_interceptor_lets_requests_through() {
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><head><title>Fixture</title></head><body>fixture</body></html>")]);
+    let page = browser.new_page("about:blank").await.unwrap();
+    let handle = NetworkInterceptor::new().block_url("*/blocked.js").attach(&page).await.unwrap();
+    drop(handle);
+
+    // With `Fetch` still enabled and nothing continuing paused requests, this would hang
+    tokio::time::timeout(std::time::Duration::from_secs(10), page.goto(format!("{}/", site)))
+        .await
+        .expect("navigation after dropping the interceptor timed out")
+        .unwrap();
+    assert_eq!(page.get_title().await.unwrap().as_deref(), Some("Fixture"));
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
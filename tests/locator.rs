@@ -0,0 +1,61 @@
+#![cfg(feature = "chromiumoxide")]
+
+use stagehand_sdk::{Selector, StagehandError};
+use std::time::{Duration, Instant};
+
+mod common;
+
+#[test]
+fn test_selector_parsing() {
+    assert_eq!(Selector::parse("#email"), Selector::Css("#email"));
+    assert_eq!(Selector::parse("css=form > input[type=email]"), Selector::Css("form > input[type=email]"));
+    assert_eq!(Selector::parse("xpath=//button[text()='Go']"), Selector::XPath("//button[text()='Go']"));
+    // Only a leading prefix selects the engine
+    assert_eq!(Selector::parse("a[href^='xpath=']"), Selector::Css("a[href^='xpath=']"));
+    assert_eq!(Selector::parse("//div"), Selector::Css("//div"));
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_locator_count_against_local_chrome() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body><li>a</li><li>b</li></body></html>")]);
+    let (mut stagehand, _server) = common::local_stagehand(&browser).await;
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+
+    assert_eq!(page.locator("li").count().await.unwrap(), 2);
+    assert_eq!(page.locator("xpath=//li").count().await.unwrap(), 2);
+    // No match is a count, not an error
+    assert_eq!(page.locator("css=table").count().await.unwrap(), 0);
+    assert_eq!(page.locator("xpath=//table").count().await.unwrap(), 0);
+    // An invalid selector is an error instead of a zero count
+    assert!(page.locator("li[").count().await.is_err());
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_locator_only_waits_for_missing_elements() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body><li>a</li></body></html>")]);
+    let (mut stagehand, _server) = common::local_stagehand(&browser).await;
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+
+    let missing = page.locator("table").timeout(Duration::from_millis(300)).wait_for().await;
+    assert!(matches!(missing, Err(StagehandError::Timeout(_))), "{:?}", missing);
+
+    // An invalid selector never matches, so it fails without waiting out the timeout
+    let started = Instant::now();
+    let invalid = page.locator("li[").timeout(Duration::from_secs(30)).wait_for().await;
+    assert!(matches!(invalid, Err(StagehandError::Cdp(_))), "{:?}", invalid);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}
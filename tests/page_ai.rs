@@ -0,0 +1,73 @@
+#![cfg(feature = "chromiumoxide")]
+
+use futures::StreamExt;
+use mockito::Matcher;
+use stagehand_sdk::{
+    ActOptions, ExtractCache, ExtractOptions, ExtractResponseEvent, InMemoryActionCache,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn finished(result: serde_json::Value) -> String {
+    format!("data: {}\n\n", serde_json::json!({ "type": "system", "data": { "status": "finished", "result": result } }))
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_page_ai_methods_send_frame_and_use_caches() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body><button id=login>Log in</button></body></html>")]);
+
+    let (mut stagehand, mut server) = common::local_stagehand(&browser).await;
+    stagehand.set_action_cache(Arc::new(InMemoryActionCache::new()));
+    stagehand.set_extract_cache(Arc::new(ExtractCache::new(Duration::from_secs(60)).check_page_content(false)));
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+    let frame_id = page.frame_id().to_string();
+
+    let login = serde_json::json!({ "selector": "#login", "description": "Log in button", "method": "click", "arguments": [] });
+    let inference = server.mock("POST", "/sessions/s1/act")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "input": "Click log in", "frameId": frame_id })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [login.clone()] })))
+        .expect(1)
+        .create_async().await;
+    let replay = server.mock("POST", "/sessions/s1/act")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "input": login, "frameId": frame_id })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "success": true, "actions": [login] })))
+        .expect(1)
+        .create_async().await;
+    let extract = server.mock("POST", "/sessions/s1/extract")
+        .match_body(Matcher::PartialJson(serde_json::json!({ "instruction": "Extract the button label", "frameId": frame_id })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(finished(serde_json::json!({ "label": "Log in" })))
+        .expect(1)
+        .create_async().await;
+
+    // The second act replays the cached action, the second extract is served from the cache
+    for _ in 0..2 {
+        let events: Vec<_> = page.act("Click log in", ActOptions::default()).await.unwrap().collect().await;
+        assert!(events.iter().all(Result::is_ok), "{:?}", events);
+
+        let schema = serde_json::json!({ "type": "object", "properties": { "label": { "type": "string" } } });
+        let mut stream = page.extract("Extract the button label", schema, ExtractOptions::default()).await.unwrap();
+        let mut data = None;
+        while let Some(item) = stream.next().await {
+            if let Some(ExtractResponseEvent::DataJson(json)) = item.unwrap().event {
+                data = Some(json);
+            }
+        }
+        assert_eq!(data.as_deref(), Some(r#"{"label":"Log in"}"#));
+    }
+
+    inference.assert_async().await;
+    replay.assert_async().await;
+    extract.assert_async().await;
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}
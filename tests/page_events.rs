@@ -0,0 +1,120 @@
+use stagehand_sdk::{ConsoleMessage, FailedRequest, PageEvent, PageException};
+
+#[cfg(feature = "chromiumoxide")]
+mod common;
+
+#[test]
+fn test_page_event_display_and_severity() {
+    let log = PageEvent::Console(ConsoleMessage { level: "log".into(), text: "ready".into(), url: None, line: None });
+    let error = PageEvent::Console(ConsoleMessage {
+        level: "error".into(),
+        text: "Payment widget failed to load".into(),
+        url: Some("https://shop.example.com/app.js".into()),
+        line: Some(42),
+    });
+    let exception = PageEvent::Exception(PageException {
+        message: "TypeError: Cannot read properties of null (reading 'click')".into(),
+        url: Some("https://shop.example.com/app.js".into()),
+        line: 7,
+        column: 13,
+    });
+    let failed = PageEvent::RequestFailed(FailedRequest {
+        method: "POST".into(),
+        url: "https://api.example.com/cart".into(),
+        error: "net::ERR_CONNECTION_REFUSED".into(),
+    });
+
+    assert!(!log.is_error());
+    assert!(error.is_error() && exception.is_error() && failed.is_error());
+    assert_eq!(error.to_string(), "console.error: Payment widget failed to load (https://shop.example.com/app.js:42)");
+    assert_eq!(
+        exception.to_string(),
+        "Uncaught TypeError: Cannot read properties of null (reading 'click') (https://shop.example.com/app.js:7:13)",
+    );
+    assert_eq!(failed.to_string(), "Request failed: POST https://api.example.com/cart (net::ERR_CONNECTION_REFUSED)");
+    assert_eq!(serde_json::to_value(&failed).unwrap()["type"], "requestFailed");
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_page_event_capture_against_local_chrome() {
+    use futures::StreamExt;
+    use stagehand_sdk::PageEventCapture;
+
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    // Nothing listens on this port, so the fetch fails
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_url = format!("http://{}/api", closed.local_addr().unwrap());
+    drop(closed);
+
+    let site = common::serve(&[("/", "text/html", "<html><body>app</body></html>")]);
+    let page = browser.new_page(format!("{}/", site)).await.unwrap();
+    let capture = PageEventCapture::start(&page).await.unwrap();
+    let mut events = capture.subscribe();
+
+    page.evaluate(format!(
+        "console.log('hello', 1, {{ a: true }}); console.error('boom'); setTimeout(() => {{ throw new Error('kaput') }}); fetch('{}').catch(() => {{}})",
+        closed_url,
+    )).await.unwrap();
+
+    let expected: [fn(&PageEvent) -> bool; 4] = [
+        |e| matches!(e, PageEvent::Console(m) if m.level == "log" && m.text == "hello 1 Object"),
+        |e| matches!(e, PageEvent::Console(m) if m.level == "error" && m.text == "boom"),
+        |e| matches!(e, PageEvent::Exception(x) if x.message.contains("kaput")),
+        |e| matches!(e, PageEvent::RequestFailed(r) if r.url.ends_with("/api")),
+    ];
+    // Events arrive in any order
+    let mut seen = Vec::new();
+    while !expected.iter().all(|matches| seen.iter().any(matches)) {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(10), events.next()).await
+            .expect("expected events within 10s").unwrap();
+        seen.push(event);
+    }
+    assert!(capture.recent_errors().iter().all(PageEvent::is_error));
+    assert!(capture.recent_errors().len() >= 3);
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_ai_errors_list_page_errors_since_the_operation_started() {
+    use futures::StreamExt;
+    use stagehand_sdk::ActOptions;
+
+    let (browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body>app</body></html>")]);
+    let (mut stagehand, mut server) = common::local_stagehand(&browser).await;
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+    let mut events = stagehand.subscribe_page_events().await.unwrap();
+
+    async fn console_error(page: &stagehand_sdk::Page, events: &mut stagehand_sdk::PageEventStream, text: &str) {
+        page.cdp().evaluate(format!("console.error('{}')", text)).await.unwrap();
+        while !matches!(events.next().await, Some(PageEvent::Console(m)) if m.text == text) {}
+    }
+
+    server.mock("POST", "/sessions/s1/act")
+        .with_header("content-type", "text/event-stream")
+        .with_body(format!("data: {}\n\n", serde_json::json!({ "type": "system", "data": { "status": "error", "error": "Element not found" } })))
+        .create_async().await;
+
+    console_error(&page, &mut events, "before").await;
+    let stream = stagehand.act("Click checkout", ActOptions::default()).await.unwrap();
+    console_error(&page, &mut events, "during").await;
+
+    let results: Vec<_> = stream.collect().await;
+    let message = results.into_iter().find_map(Result::err).expect("act fails").to_string();
+    assert!(message.starts_with("API error: Element not found\nRecent page errors:"), "{}", message);
+    assert!(message.contains("console.error: during"), "{}", message);
+    assert!(!message.contains("before"), "{}", message);
+    assert_eq!(stagehand.recent_page_errors().len(), 2);
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}
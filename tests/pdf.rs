@@ -0,0 +1,200 @@
+#![cfg(feature = "pdf-text")]
+
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use stagehand_sdk::{StagehandError, pdf_text};
+use std::io::Write;
+
+#[cfg(feature = "chromiumoxide")]
+mod common;
+
+/// A PDF file with `objects` numbered from 1, object 1 being the catalog
+fn build_pdf(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes());
+    pdf
+}
+
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_pdf_text_of_simple_pages() {
+    let first = b"BT /F1 24 Tf 72 720 Td (Invoice \\(draft\\)) Tj ET BT /F1 24 Tf 72 690 Td (Total: $42) Tj ET";
+    let second = b"BT /F1 12 Tf 1 0 0 1 72 700 Tm [(Thank)-250(you)] TJ ET";
+    // Both pages inherit their font from the page tree's resources
+    let pdf = build_pdf(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 /Resources 5 0 R >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 6 0 R >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 7 0 R >>".to_vec(),
+        b"<< /Font << /F1 8 0 R >> >>".to_vec(),
+        stream("", first),
+        stream("", second),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+    ]);
+
+    assert_eq!(pdf_text(&pdf).unwrap(), "Invoice (draft)\nTotal: $42\n\nThank you");
+}
+
+#[test]
+fn test_pdf_text_of_compressed_unicode_fonts() {
+    // Glyph ids 1-3 and 4 map to text through the font's ToUnicode CMap, as in Chrome's output
+    let cmap = b"/CIDInit /ProcSet findresource begin
+12 dict begin
+begincmap
+/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def
+/CMapName /Adobe-Identity-UCS def
+/CMapType 2 def
+1 begincodespacerange <0000> <FFFF> endcodespacerange
+2 beginbfchar <0004> <0020> <0005> <00E9> endbfchar
+1 beginbfrange <0001> <0003> <0043> endbfrange
+endcmap
+CMapName currentdict /CMap defineresource pop
+end
+end";
+    let content = b"q BT /F1 10 Tf 1 0 0 -1 50 100 Tm <000100020003> Tj ET Q
+BT /F1 10 Tf 1 0 0 1 50 80 Tm [<0001>-30<0005>] TJ 60 0 Td <00010002> Tj ET";
+    let pdf = build_pdf(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type0 /Encoding /Identity-H /DescendantFonts [6 0 R] /ToUnicode 7 0 R >>".to_vec(),
+        stream("/Filter /FlateDecode", &deflate(content)),
+        b"<< /Type /Font /Subtype /CIDFontType2 /DW 500 /W [1 [600 600 600]] >>".to_vec(),
+        stream("/Filter /FlateDecode", &deflate(cmap)),
+    ]);
+
+    assert_eq!(pdf_text(&pdf).unwrap(), "CDE\nCé CD");
+}
+
+#[test]
+fn test_pdf_text_survives_out_of_range_numbers() {
+    // Huge lengths, offsets and character codes are skipped rather than overflowing
+    let cmap = b"1 begincodespacerange <00000000> <FFFFFFFF> endcodespacerange
+1 beginbfrange <FFFFFFFF> <FFFFFFFF> <004F> endbfrange";
+    let pdf = build_pdf(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 4 0 R /F2 6 0 R >> >> /Contents 5 0 R >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type0 /DescendantFonts [7 0 R] /ToUnicode 8 0 R >>".to_vec(),
+        b"<< /Length 18446744073709551615 >>\nstream\nBT /F1 10 Tf <FFFFFFFF> Tj /F2 10 Tf (k) Tj ET\nendstream".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /FirstChar 4294967295 /Widths [500 500] >>".to_vec(),
+        b"<< /Type /Font /Subtype /CIDFontType2 /W [4294967295 [600 600] 4294967295 4294967295 600] >>".to_vec(),
+        stream("", cmap),
+        stream("/Type /ObjStm /N 1 /First 18446744073709551615 /Filter /FlateDecode", &deflate(b"10 1  << >>")),
+    ]);
+
+    assert!(pdf_text(&pdf).is_ok());
+}
+
+#[test]
+fn test_pdf_text_caps_inflated_streams() {
+    // 100 MB of spaces compresses to about 100 KB; inflating stops at 64 MB
+    let mut content = b"BT /F1 10 Tf (bomb) Tj ET".to_vec();
+    content.resize(100 * 1024 * 1024, b' ');
+    let pdf = build_pdf(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>".to_vec(),
+        stream("/Filter /FlateDecode", &deflate(&content)),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+    ]);
+    drop(content);
+
+    let err = pdf_text(&pdf).unwrap_err();
+    assert!(matches!(err, StagehandError::Pdf(_)), "{}", err);
+}
+
+#[test]
+fn test_pdf_text_caps_page_content() {
+    // One 1 MB stream listed 100 times stops inflating once the page's content reaches 64 MB
+    let mut content = b"BT /F1 10 Tf (bomb) Tj ET".to_vec();
+    content.resize(1024 * 1024, b' ');
+    let contents = format!("[{}]", vec!["4 0 R"; 100].join(" "));
+    let pdf = build_pdf(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!("<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 5 0 R >> >> /Contents {} >>", contents).into_bytes(),
+        stream("/Filter /FlateDecode", &deflate(&content)),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+    ]);
+
+    let err = pdf_text(&pdf).unwrap_err();
+    assert!(matches!(err, StagehandError::Pdf(_)), "{}", err);
+}
+
+#[test]
+fn test_pdf_text_survives_deep_nesting() {
+    // An object of 200,000 nested arrays, and a page tree 100,000 levels deep, would overflow
+    // the stack if parsing followed every level
+    let mut nested = b"%PDF-1.7\n1 0 obj\n".to_vec();
+    nested.resize(nested.len() + 200_000, b'[');
+    assert!(matches!(pdf_text(&nested), Err(StagehandError::Pdf(_))));
+
+    // Page tree nodes from object 5 on, each the only kid of the one before, the last
+    // holding the page
+    let page_tree = |depth: usize| {
+        let mut objects = vec![
+            b"<< /Type /Catalog /Pages 5 0 R >>".to_vec(),
+            b"<< /Type /Page /Resources << /Font << /F1 3 0 R >> >> /Contents 4 0 R >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+            stream("", b"BT /F1 10 Tf (deep) Tj ET"),
+        ];
+        for i in 0..depth {
+            let kid = if i + 1 == depth { 2 } else { i + 6 };
+            objects.push(format!("<< /Type /Pages /Kids [{} 0 R] /Count 1 >>", kid).into_bytes());
+        }
+        build_pdf(&objects)
+    };
+    assert!(pdf_text(&page_tree(100_000)).is_ok());
+    assert_eq!(pdf_text(&page_tree(10)).unwrap(), "deep");
+}
+
+#[test]
+fn test_pdf_text_rejects_other_files() {
+    let err = pdf_text(b"<html><body>Not found</body></html>").unwrap_err();
+    assert!(matches!(err, StagehandError::Pdf(_)), "{}", err);
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_printed_pdf_text_against_local_chrome() {
+    use stagehand_sdk::PdfOptions;
+
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body><h1>Quarterly report</h1><p>Revenue grew 12%</p></body></html>")]);
+    let page = browser.new_page(format!("{}/", site)).await.unwrap();
+
+    let pdf = page.pdf(PdfOptions::new().print_background(true).into()).await.unwrap();
+    let text = pdf_text(&pdf).unwrap();
+    assert!(text.contains("Quarterly report"), "{}", text);
+    assert!(text.contains("Revenue grew 12%"), "{}", text);
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
@@ -0,0 +1,40 @@
+use stagehand_sdk::{BrowserbaseClient, RecordingEvent, cdn_replay_html, write_cdn_replay_html};
+
+#[tokio::test]
+async fn test_session_recording() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/sessions/s1/recording")
+        .with_body(serde_json::json!([
+            { "type": 4, "timestamp": 1000, "data": { "href": "https://example.com" }, "sessionId": "s1" },
+            { "type": 2, "timestamp": 1001, "data": { "node": {} }, "sessionId": "s1" },
+        ]).to_string())
+        .create_async().await;
+
+    let client = BrowserbaseClient::new("key", "proj").with_base_url(server.url());
+    let events = client.session_recording("s1").await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].event_type, 2);
+    assert_eq!(events[0].data["href"], "https://example.com");
+}
+
+#[test]
+fn test_replay_html() {
+    let events = vec![RecordingEvent {
+        event_type: 3,
+        timestamp: 1000,
+        data: serde_json::json!({ "text": "</script><script>alert(1)</script>" }),
+        session_id: None,
+    }];
+
+    let html = cdn_replay_html(&events, "Run <42>").unwrap();
+    assert!(html.contains("<title>Run &lt;42&gt;</title>"));
+    assert!(html.contains("rrwebPlayer"));
+    // Page content can't break out of the events script
+    assert_eq!(html.matches("</script>").count(), 2);
+    assert!(!html.contains("<script>alert(1)"));
+
+    let path = std::env::temp_dir().join(format!("stagehand-replay-{}.html", std::process::id()));
+    write_cdn_replay_html(&events, "Run <42>", &path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), html);
+    std::fs::remove_file(&path).unwrap();
+}
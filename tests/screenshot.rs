@@ -0,0 +1,67 @@
+#![cfg(feature = "chromiumoxide")]
+
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, CaptureScreenshotParams, PrintToPdfParams};
+use stagehand_sdk::{ClipRect, ImageFormat, PdfOptions, ScreenshotOptions, StagehandError};
+
+#[test]
+fn test_screenshot_options_to_params() {
+    let params = CaptureScreenshotParams::try_from(&ScreenshotOptions::new()).unwrap();
+    assert_eq!(params.format, Some(CaptureScreenshotFormat::Png));
+    assert_eq!(params.quality, None);
+    assert_eq!(params.clip, None);
+    assert_eq!(params.capture_beyond_viewport, None);
+
+    let clip = ClipRect { x: 10.0, y: 20.0, width: 300.0, height: 200.0 };
+    let options = ScreenshotOptions::new().format(ImageFormat::Jpeg).quality(80).clip(clip);
+    let params = CaptureScreenshotParams::try_from(&options).unwrap();
+    assert_eq!(params.format, Some(CaptureScreenshotFormat::Jpeg));
+    assert_eq!(params.quality, Some(80));
+    let viewport = params.clip.unwrap();
+    assert_eq!((viewport.x, viewport.y, viewport.width, viewport.height, viewport.scale), (10.0, 20.0, 300.0, 200.0, 1.0));
+    assert_eq!(params.capture_beyond_viewport, Some(true));
+
+    // PNG is lossless, so Chrome would reject a quality
+    let params = CaptureScreenshotParams::try_from(&ScreenshotOptions::new().quality(80)).unwrap();
+    assert_eq!(params.quality, None);
+}
+
+#[test]
+fn test_screenshot_quality_is_validated() {
+    let options = ScreenshotOptions::new().format(ImageFormat::Webp).quality(100);
+    assert_eq!(CaptureScreenshotParams::try_from(&options).unwrap().quality, Some(100));
+
+    let options = ScreenshotOptions::new().format(ImageFormat::Webp).quality(101);
+    let err = CaptureScreenshotParams::try_from(&options).unwrap_err();
+    assert!(matches!(err, StagehandError::Cdp(ref msg) if msg.contains("101")), "{}", err);
+}
+
+#[test]
+fn test_pdf_options_to_print_params() {
+    let params = PrintToPdfParams::from(
+        PdfOptions::new()
+            .landscape(true)
+            .scale(0.8)
+            .paper_size(8.27, 11.69)
+            .margins(0.5, 0.25, 0.75, 1.0)
+            .page_ranges("1-3")
+            .footer_template("<span class=pageNumber></span>"),
+    );
+    assert_eq!(params.landscape, Some(true));
+    assert_eq!(params.print_background, Some(false));
+    assert_eq!(params.scale, Some(0.8));
+    assert_eq!((params.paper_width, params.paper_height), (Some(8.27), Some(11.69)));
+    assert_eq!(
+        (params.margin_top, params.margin_right, params.margin_bottom, params.margin_left),
+        (Some(0.5), Some(0.25), Some(0.75), Some(1.0)),
+    );
+    assert_eq!(params.page_ranges.as_deref(), Some("1-3"));
+    // Either template turns on header and footer printing
+    assert_eq!(params.display_header_footer, Some(true));
+    assert_eq!(params.header_template, None);
+    assert_eq!(params.footer_template.as_deref(), Some("<span class=pageNumber></span>"));
+    assert_eq!(params.prefer_css_page_size, Some(false));
+
+    let defaults = PrintToPdfParams::from(PdfOptions::default());
+    assert_eq!(defaults.display_header_footer, Some(false));
+    assert_eq!((defaults.scale, defaults.paper_width, defaults.margin_top, defaults.page_ranges), (None, None, None, None));
+}
@@ -0,0 +1,130 @@
+use stagehand_sdk::{SameSite, StorageItem, StorageState};
+
+mod common;
+
+/// As written by Playwright's `context.storageState({ path })`
+const PLAYWRIGHT_STATE: &str = r#"{
+  "cookies": [
+    {
+      "name": "session",
+      "value": "abc123",
+      "domain": ".example.com",
+      "path": "/",
+      "expires": 1798761600.5,
+      "httpOnly": true,
+      "secure": true,
+      "sameSite": "Strict"
+    },
+    {
+      "name": "theme",
+      "value": "dark",
+      "domain": "app.example.com",
+      "path": "/",
+      "expires": -1,
+      "httpOnly": false,
+      "secure": false,
+      "sameSite": "Lax"
+    }
+  ],
+  "origins": [
+    {
+      "origin": "https://app.example.com",
+      "localStorage": [{ "name": "token", "value": "xyz" }]
+    }
+  ]
+}"#;
+
+#[test]
+fn test_playwright_storage_state_round_trip() {
+    let state: StorageState = serde_json::from_str(PLAYWRIGHT_STATE).unwrap();
+    assert_eq!(state.cookies[0].same_site, SameSite::Strict);
+    assert_eq!(state.cookies[1].expires, -1.0);
+    assert_eq!(state.origins[0].local_storage, vec![StorageItem { name: "token".into(), value: "xyz".into() }]);
+    assert!(state.origins[0].session_storage.is_empty());
+
+    // Without session storage the output is plain Playwright JSON
+    let json = serde_json::to_value(&state).unwrap();
+    assert_eq!(json["cookies"][0]["httpOnly"], true);
+    assert_eq!(json["origins"][0], serde_json::json!({ "origin": "https://app.example.com", "localStorage": [{ "name": "token", "value": "xyz" }] }));
+    assert_eq!(serde_json::from_value::<StorageState>(json).unwrap(), state);
+}
+
+#[test]
+fn test_storage_state_file() {
+    let path = std::env::temp_dir().join(format!("stagehand-storage-{}.json", std::process::id()));
+    let mut state: StorageState = serde_json::from_str(PLAYWRIGHT_STATE).unwrap();
+    state.origins[0].session_storage.push(StorageItem { name: "step".into(), value: "2".into() });
+
+    state.write_to(&path).unwrap();
+    assert_eq!(StorageState::from_file(&path).unwrap(), state);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(StorageState::from_file(&path).is_err());
+}
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_storage_state_against_local_chrome() {
+    let (mut browser, handler_task) = common::launch_chrome().await;
+    let site = common::serve(&[("/", "text/html", "<html><body>app</body></html>")]);
+    let host = site.trim_start_matches("http://").split(':').next().unwrap().to_string();
+
+    let mut state: StorageState = serde_json::from_str(PLAYWRIGHT_STATE).unwrap();
+    state.cookies.retain(|c| c.name == "theme");
+    state.cookies[0].domain = host;
+    state.origins[0].origin = site.clone();
+    state.origins[0].session_storage.push(StorageItem { name: "step".into(), value: "2".into() });
+
+    let page = browser.new_page("about:blank").await.unwrap();
+    state.apply(&browser, &page).await.unwrap();
+    assert_eq!(page.url().await.unwrap().as_deref(), Some("about:blank"), "the page is not navigated");
+    assert_eq!(browser.pages().await.unwrap().len(), 1, "the temporary page is closed");
+
+    // The site sees the state once the page visits it
+    page.goto(format!("{}/", site)).await.unwrap();
+    let seen: serde_json::Value = page
+        .evaluate("({ cookie: document.cookie, token: localStorage.getItem('token'), step: sessionStorage.getItem('step') })")
+        .await
+        .unwrap()
+        .into_value()
+        .unwrap();
+    assert_eq!(seen, serde_json::json!({ "cookie": "theme=dark", "token": "xyz", "step": "2" }));
+
+    let captured = StorageState::capture(&browser).await.unwrap();
+    assert!(captured.cookies.iter().any(|c| c.name == "theme" && c.value == "dark"));
+    assert_eq!(captured.origins, state.origins);
+
+    browser.close().await.unwrap();
+    handler_task.abort();
+}
+
+#[cfg(not(feature = "chromiumoxide"))]
+#[tokio::test]
+async fn test_start_with_storage_state_needs_chromiumoxide() {
+    use stagehand_sdk::{StagehandError, V3Options};
+
+    let server = common::mock_api().await;
+    let mut stagehand = common::connect(&server).await;
+
+    let opts = V3Options { storage_state: Some(StorageState::default()), ..Default::default() };
+    assert!(matches!(stagehand.start(opts).await, Err(StagehandError::Api(message)) if message.contains("chromiumoxide")));
+}
+
+#[cfg(feature = "chromiumoxide")]
+#[tokio::test]
+async fn test_start_ends_session_when_storage_state_fails() {
+    use stagehand_sdk::V3Options;
+
+    let mut server = common::mock_api().await;
+    // The session's CDP URL can't be fetched, so the state can't be applied
+    server.mock("GET", "/sessions/s1").with_status(500).create_async().await;
+    let end = server.mock("POST", "/sessions/s1/end").expect(1).create_async().await;
+    let mut stagehand = common::connect(&server).await;
+
+    let opts = V3Options { storage_state: Some(serde_json::from_str(PLAYWRIGHT_STATE).unwrap()), ..Default::default() };
+    assert!(stagehand.start(opts).await.is_err());
+    end.assert_async().await;
+    assert_eq!(stagehand.session_id(), None);
+}
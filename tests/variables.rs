@@ -0,0 +1,82 @@
+use stagehand_sdk::{templated_instruction, SecretString, StagehandError, VariableValue};
+
+mod common;
+
+#[test]
+fn test_secret_is_masked_in_debug() {
+    let secret = SecretString::new("hunter2");
+    assert!(!format!("{:?}", secret).contains("hunter2"));
+
+    let value = VariableValue::from(secret);
+    assert!(!format!("{:?}", value).contains("hunter2"));
+    assert_eq!(value.expose(), "hunter2");
+}
+
+#[test]
+fn test_secret_is_sent_to_server() {
+    let value = VariableValue::from(SecretString::new("hunter2"));
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#""hunter2""#);
+}
+
+#[test]
+fn test_templated_instruction() {
+    let (instruction, variables) = templated_instruction!(
+        "Type %password% into the password field and save 50% off",
+        password = SecretString::new("hunter2"),
+    );
+    assert_eq!(instruction, "Type %password% into the password field and save 50% off");
+    assert!(variables["password"].is_secret());
+    assert!(!format!("{:?}", variables).contains("hunter2"));
+}
+
+#[tokio::test]
+async fn test_secret_is_redacted_from_act_stream() {
+    use futures::StreamExt;
+    use stagehand_sdk::{ActOptions, ActResponseEvent, V3Options};
+
+    let mut server = common::mock_api().await;
+    let events: String = [
+        serde_json::json!({ "type": "log", "data": { "message": "Typing hunter2 into the password field" } }),
+        serde_json::json!({ "type": "system", "data": { "status": "finished", "result": {
+            "success": true,
+            "actions": [{ "selector": "#password", "description": "Password field, filled with hunter2", "method": "fill", "arguments": ["hunter2"] }],
+        } } }),
+        serde_json::json!({ "type": "system", "data": { "status": "error", "error": "Login rejected password hunter2" } }),
+    ].iter().map(|event| format!("data: {}\n\n", event)).collect();
+    let act = server.mock("POST", "/sessions/s1/act")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "options": { "variables": { "password": "hunter2" } } })))
+        .with_header("content-type", "text/event-stream")
+        .with_body(events)
+        .create_async().await;
+
+    let mut stagehand = common::connect(&server).await;
+    stagehand.start(V3Options::default()).await.unwrap();
+    let (instruction, variables) = templated_instruction!("Type %password% into the password field", password = SecretString::new("hunter2"));
+    let events: Vec<_> = stagehand.act(instruction, ActOptions::new().variables(variables)).await.unwrap().collect().await;
+    act.assert_async().await;
+
+    // The secret reaches the server, but the caller sees only its placeholder
+    assert!(!format!("{:?}", events).contains("hunter2"), "{:?}", events);
+    let mut seen = (false, false, false);
+    for event in events {
+        match event {
+            Ok(response) => match response.event {
+                Some(ActResponseEvent::Log(log)) => {
+                    assert_eq!(log.message, "Typing %password% into the password field");
+                    seen.0 = true;
+                }
+                Some(ActResponseEvent::Actions(actions)) => {
+                    assert_eq!(actions[0].description, "Password field, filled with %password%");
+                    assert_eq!(actions[0].arguments, vec!["%password%".to_string()]);
+                    seen.1 = true;
+                }
+                _ => {}
+            },
+            Err(e) => {
+                assert_eq!(e.to_string(), StagehandError::Api("Login rejected password %password%".to_string()).to_string());
+                seen.2 = true;
+            }
+        }
+    }
+    assert_eq!(seen, (true, true, true));
+}
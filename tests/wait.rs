@@ -0,0 +1,55 @@
+#![cfg(feature = "chromiumoxide")]
+
+use stagehand_sdk::{LoadState, StagehandError};
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Needs a locally installed Chrome; run with `cargo test -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Chrome"]
+async fn test_waits_time_out_and_propagate_errors() {
+    let (browser, handler_task) = common::launch_chrome().await;
+    // The button is added 300 ms after load
+    let site = common::serve(&[(
+        "/",
+        "text/html",
+        "<html><body><script>setTimeout(() => document.body.innerHTML = '<button id=later>Go</button>', 300)</script></body></html>",
+    )]);
+    let (mut stagehand, _server) = common::local_stagehand(&browser).await;
+    let page = stagehand.new_page(format!("{}/", site)).await.unwrap();
+
+    page.wait_for_load_state(LoadState::Load, Duration::from_secs(5)).await.unwrap();
+    page.wait_for_selector("#later", Duration::from_secs(5)).await.unwrap();
+    page.wait_for_url("http://127.0.0.1:*/", Duration::from_secs(1)).await.unwrap();
+
+    // A condition that never holds fails with a timeout close to the deadline
+    let started = Instant::now();
+    let err = page.wait_for_selector("#never", Duration::from_millis(300)).await.unwrap_err();
+    assert!(matches!(err, StagehandError::Timeout(ref msg) if msg.contains("#never")), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(2), "{:?}", started.elapsed());
+
+    let err = page.wait_for_url("**/checkout", Duration::from_millis(300)).await.unwrap_err();
+    assert!(matches!(err, StagehandError::Timeout(ref msg) if msg.contains("**/checkout")), "{}", err);
+
+    let err = page.locator("#never").timeout(Duration::from_millis(300)).click().await.unwrap_err();
+    assert!(matches!(err, StagehandError::Timeout(_)), "{}", err);
+
+    // An invalid selector fails with its CDP error instead of waiting for the timeout
+    let started = Instant::now();
+    let err = page.wait_for_selector("button[", Duration::from_secs(10)).await.unwrap_err();
+    assert!(matches!(err, StagehandError::Cdp(_)), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(1), "{:?}", started.elapsed());
+
+    // A failing predicate ends the wait with its own error instead of waiting for the timeout
+    let started = Instant::now();
+    let err = page.wait_for(|_| async { Err::<bool, _>(StagehandError::Cdp("target closed".to_string())) }, Duration::from_secs(10))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, StagehandError::Cdp(ref msg) if msg == "target closed"), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(1), "{:?}", started.elapsed());
+
+    drop(stagehand);
+    drop(browser);
+    handler_task.abort();
+}